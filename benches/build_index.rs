@@ -0,0 +1,106 @@
+// 全量重建索引的基准测试：在临时目录中生成合成词库（默认 200k 个词条，按前两个字母分目录），
+// 比较单线程读取解析 + 50 MB 写入缓冲（并行化之前的行为）与多线程和更大内存预算的耗时。
+//
+//     cargo bench --bench build_index
+//     DICTNAVI_BENCH_ENTRIES=20000 cargo bench --bench build_index    # 较小的词库
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, SamplingMode};
+use serde_json::json;
+
+use DictNavi::dictionary::{BuildSettings, Dictionary, DEFAULT_WRITER_MEMORY};
+
+const DEFAULT_ENTRIES: usize = 200_000;
+
+const SYLLABLES: [&str; 16] = [
+    "ba", "ce", "di", "fo", "gu", "ha", "je", "ki", "lo", "mu", "na", "pe", "ri", "so", "tu", "vy",
+];
+
+// 由序号生成不重复的合成单词，例如 0 -> "baba"
+fn synthetic_word(mut n: usize) -> String {
+    let mut word = String::new();
+    for _ in 0..2 {
+        word.push_str(SYLLABLES[n % SYLLABLES.len()]);
+        n /= SYLLABLES.len();
+    }
+    while n > 0 {
+        word.push_str(SYLLABLES[n % SYLLABLES.len()]);
+        n /= SYLLABLES.len();
+    }
+    word
+}
+
+// 与真实词条结构相同：词形变化、三条释义（中英文解释和例句）和一条辨析
+fn write_corpus(words_dir: &Path, entries: usize) {
+    for n in 0..entries {
+        let word = synthetic_word(n);
+        let entry = json!({
+            "word": word,
+            "pronunciation": format!("{}·{}", &word[..2], &word[2..]),
+            "concise_definition": format!("v. 合成词条 {}, 用于基准测试", n),
+            "forms": {
+                "third_person_singular": format!("{}s", word),
+                "past_tense": format!("{}ed", word),
+                "past_participle": format!("{}ed", word),
+                "present_participle": format!("{}ing", word),
+            },
+            "definitions": (0..3).map(|i| json!({
+                "pos": "verb",
+                "explanation_en": format!("To {} something in the {} way, often used when describing a change that happens slowly over a long period of time.", word, i + 1),
+                "explanation_cn": format!("指以第 {} 种方式{}某物，常用于描述在很长一段时间内缓慢发生的变化。", i + 1, word),
+                "example_en": format!("They had to {} the old bridge before the winter came.", word),
+                "example_cn": "他们不得不在冬天到来之前处理那座旧桥。",
+            })).collect::<Vec<_>>(),
+            "comparison": [{
+                "word_to_compare": synthetic_word(n + 1),
+                "analysis": format!("{} 更强调过程，{} 更强调结果。", word, synthetic_word(n + 1)),
+            }],
+        });
+
+        let shard = words_dir.join(&word[..2]);
+        fs::create_dir_all(&shard).expect("create shard directory");
+        fs::write(shard.join(format!("{}.json", word)), entry.to_string()).expect("write word file");
+    }
+}
+
+fn build_index(c: &mut Criterion) {
+    let entries = std::env::var("DICTNAVI_BENCH_ENTRIES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_ENTRIES);
+
+    let temp = tempfile::tempdir().expect("create temp directory");
+    let words_dir = temp.path().join("words");
+    let index_dir = temp.path().join("index");
+    write_corpus(&words_dir, entries);
+
+    let runtime = tokio::runtime::Runtime::new().expect("start tokio runtime");
+    let cases = [
+        ("sequential-50MB", BuildSettings { writer_memory: DEFAULT_WRITER_MEMORY, threads: 1 }),
+        ("parallel-50MB", BuildSettings { writer_memory: DEFAULT_WRITER_MEMORY, threads: 0 }),
+        ("parallel-200MB", BuildSettings { writer_memory: 200_000_000, threads: 0 }),
+    ];
+
+    let mut group = c.benchmark_group("build_index");
+    // 每次迭代都是一次完整的重建（数秒），用最少的样本数，每个样本只迭代一次
+    group.sampling_mode(SamplingMode::Flat);
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(30));
+    for (name, settings) in cases {
+        let dictionary = Dictionary::with_index_path(
+            words_dir.to_string_lossy().into_owned(),
+            index_dir.to_string_lossy().into_owned(),
+        )
+        .with_build_settings(settings);
+        group.bench_with_input(BenchmarkId::new(name, entries), &dictionary, |b, dictionary| {
+            b.iter(|| runtime.block_on(dictionary.rebuild_index_async()).expect("rebuild index"));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, build_index);
+criterion_main!(benches);
@@ -0,0 +1,178 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use fst::automaton::{Automaton, Str};
+use fst::{IntoStreamer, Set, Streamer};
+
+use crate::models::normalize_headword;
+
+// 自动完成用的 FST 文件与 tantivy 索引文件放在同一目录下
+pub const AUTOCOMPLETE_FILE: &str = "autocomplete.fst";
+
+// 键的格式为 “规范化词头 \0 原始词头”：按规范化形式做前缀匹配，同时保留原始大小写用于显示
+const SEPARATOR: u8 = 0;
+
+// 基于 FST 的词头前缀索引，完全在内存中查询，不访问 tantivy 文档存储
+pub struct Autocomplete {
+    set: Set<Vec<u8>>,
+}
+
+impl Autocomplete {
+    // 根据词头列表构建 FST 并写入索引目录（先写临时文件再重命名）
+    pub fn build<'a>(words: impl Iterator<Item = &'a str>, index_dir: &str) -> Result<(), String> {
+        let keys: BTreeSet<Vec<u8>> = words
+            .map(|word| word.trim())
+            .filter(|word| !word.is_empty())
+            .map(|word| {
+                let mut key = normalize_headword(word).into_bytes();
+                key.push(SEPARATOR);
+                key.extend_from_slice(word.as_bytes());
+                key
+            })
+            .collect();
+
+        let set = Set::from_iter(keys).map_err(|e| format!("Failed to build autocomplete index: {}", e))?;
+
+        let final_path = Path::new(index_dir).join(AUTOCOMPLETE_FILE);
+        let tmp_path = Path::new(index_dir).join(format!("{}.tmp", AUTOCOMPLETE_FILE));
+        fs::write(&tmp_path, set.as_fst().as_bytes())
+            .map_err(|e| format!("Failed to write autocomplete index: {}", e))?;
+        fs::rename(&tmp_path, &final_path).map_err(|e| format!("Failed to write autocomplete index: {}", e))
+    }
+
+    // 读取索引目录中的 FST；文件不存在或损坏时返回 None
+    pub fn load(index_dir: &str) -> Option<Self> {
+        let bytes = fs::read(Path::new(index_dir).join(AUTOCOMPLETE_FILE)).ok()?;
+        Set::new(bytes).ok().map(|set| Autocomplete { set })
+    }
+
+    // 以 prefix 开头（不区分大小写）的词头，按字母顺序返回至多 limit 个
+    pub fn complete(&self, prefix: &str, limit: usize) -> Vec<String> {
+        let prefix = normalize_prefix(prefix);
+        let matcher = Str::new(&prefix).starts_with();
+        let mut stream = self.set.search(matcher).into_stream();
+
+        let mut words = Vec::new();
+        while words.len() < limit {
+            let Some(key) = stream.next() else {
+                break;
+            };
+            words.push(display_word(key));
+        }
+        words
+    }
+
+    // 全部词头（按小写字母顺序）
+    pub fn all_words(&self) -> Vec<String> {
+        let mut stream = self.set.stream();
+        let mut words = Vec::with_capacity(self.set.len());
+        while let Some(key) = stream.next() {
+            words.push(display_word(key));
+        }
+        words
+    }
+}
+
+// 与词头相同的规范化，但保留末尾的空格，使 "give " 只匹配以 "give " 开头的词组
+fn normalize_prefix(prefix: &str) -> String {
+    let normalized = normalize_headword(prefix);
+    if !normalized.is_empty() && prefix.ends_with(char::is_whitespace) {
+        normalized + " "
+    } else {
+        normalized
+    }
+}
+
+fn display_word(key: &[u8]) -> String {
+    let original = match key.iter().position(|&b| b == SEPARATOR) {
+        Some(pos) => &key[pos + 1..],
+        None => key,
+    };
+    String::from_utf8_lossy(original).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::Dictionary;
+
+    fn build(words: &[&str]) -> (tempfile::TempDir, Autocomplete) {
+        let temp = tempfile::tempdir().unwrap();
+        let index_dir = temp.path().to_string_lossy().into_owned();
+        Autocomplete::build(words.iter().copied(), &index_dir).unwrap();
+        let autocomplete = Autocomplete::load(&index_dir).unwrap();
+        (temp, autocomplete)
+    }
+
+    #[test]
+    fn prefix_matching_ignores_case_and_keeps_original_spelling() {
+        let (_temp, autocomplete) = build(&["Give", "give up", "given", "GIF", "gave", "  giving  "]);
+        assert_eq!(autocomplete.complete("gi", 10), ["GIF", "Give", "give up", "given", "giving"]);
+        assert_eq!(autocomplete.complete("GIV", 10), ["Give", "give up", "given", "giving"]);
+        assert_eq!(autocomplete.complete("  Giv", 10), ["Give", "give up", "given", "giving"]);
+        assert!(autocomplete.complete("go", 10).is_empty());
+    }
+
+    #[test]
+    fn trailing_space_restricts_to_phrases() {
+        let (_temp, autocomplete) = build(&["give", "give up", "give  in", "given"]);
+        assert_eq!(autocomplete.complete("give ", 10), ["give  in", "give up"]);
+        assert_eq!(autocomplete.complete("give", 10), ["give", "give  in", "give up", "given"]);
+        assert_eq!(normalize_prefix("Give "), "give ");
+        assert_eq!(normalize_prefix("   "), "");
+    }
+
+    #[test]
+    fn respects_the_limit() {
+        let (_temp, autocomplete) = build(&["a", "ab", "abc", "abd", "b"]);
+        assert_eq!(autocomplete.complete("a", 2), ["a", "ab"]);
+        assert!(autocomplete.complete("a", 0).is_empty());
+        // 空前缀匹配全部词头
+        assert_eq!(autocomplete.complete("", 10), ["a", "ab", "abc", "abd", "b"]);
+    }
+
+    #[test]
+    fn keys_separate_normalized_and_original_forms() {
+        let (_temp, autocomplete) = build(&["Rock’n’Roll", "", "   "]);
+        assert_eq!(autocomplete.set.len(), 1);
+        let mut stream = autocomplete.set.stream();
+        let key = stream.next().unwrap().to_vec();
+        assert_eq!(key, "rock'n'roll\0Rock’n’Roll".as_bytes());
+        assert_eq!(display_word(&key), "Rock’n’Roll");
+        assert_eq!(display_word(b"plain"), "plain");
+        assert_eq!(autocomplete.all_words(), ["Rock’n’Roll"]);
+    }
+
+    #[test]
+    fn missing_or_corrupt_file_is_not_loaded() {
+        let temp = tempfile::tempdir().unwrap();
+        let index_dir = temp.path().to_string_lossy().into_owned();
+        assert!(Autocomplete::load(&index_dir).is_none());
+        fs::write(temp.path().join(AUTOCOMPLETE_FILE), b"not an fst").unwrap();
+        assert!(Autocomplete::load(&index_dir).is_none());
+    }
+
+    #[test]
+    fn completions_follow_index_syncs() {
+        let temp = tempfile::tempdir().unwrap();
+        let words_dir = temp.path().join("words");
+        fs::create_dir_all(&words_dir).unwrap();
+        fs::write(words_dir.join("give.json"), r#"{"word": "give"}"#).unwrap();
+        let dictionary = Dictionary::with_index_path(
+            words_dir.to_string_lossy().into_owned(),
+            temp.path().join("index").to_string_lossy().into_owned(),
+        );
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(dictionary.rebuild_index_async()).unwrap();
+        assert_eq!(dictionary.complete_prefix("gi", 10).unwrap(), ["give"]);
+
+        fs::write(words_dir.join("given.json"), r#"{"word": "Given"}"#).unwrap();
+        fs::remove_file(words_dir.join("give.json")).unwrap();
+        runtime.block_on(dictionary.sync_index_async()).unwrap();
+        assert_eq!(dictionary.complete_prefix("gi", 10).unwrap(), ["Given"]);
+
+        runtime.block_on(dictionary.rebuild_index_async()).unwrap();
+        assert_eq!(dictionary.complete_prefix("GI", 10).unwrap(), ["Given"]);
+    }
+}
@@ -0,0 +1,253 @@
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::dictionary_set::DictionarySet;
+use crate::models::{Inflection, WordDefinition};
+use crate::render;
+
+// 批量查词：逐行读取单词，输出查到的词条，并汇总未找到的单词
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchFormat {
+    Jsonl,
+    Csv,
+    Markdown,
+}
+
+impl FromStr for BatchFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "jsonl" | "json" => Ok(BatchFormat::Jsonl),
+            "csv" => Ok(BatchFormat::Csv),
+            "markdown" | "md" => Ok(BatchFormat::Markdown),
+            other => Err(format!("unknown format '{}', expected jsonl, csv or markdown", other)),
+        }
+    }
+}
+
+impl fmt::Display for BatchFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BatchFormat::Jsonl => "jsonl",
+            BatchFormat::Csv => "csv",
+            BatchFormat::Markdown => "markdown",
+        })
+    }
+}
+
+// JSONL 中的一行：输入的单词、词条所在的词典、通过屈折形式命中时的形式信息和词条
+#[derive(Serialize)]
+struct BatchRecord<'a> {
+    query: &'a str,
+    source: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inflection: Option<&'a Inflection>,
+    entry: &'a WordDefinition,
+}
+
+// 未找到的单词及其在输入中的行号
+#[derive(Debug, Clone, Serialize)]
+pub struct NotFound {
+    pub line: usize,
+    pub word: String,
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BatchReport {
+    pub found: usize,
+    pub not_found: Vec<NotFound>,
+}
+
+// 逐行查询 input 中的单词（跳过空行和以 # 开头的注释行），按 format 写入 output
+pub fn run_batch(dictionary: &DictionarySet, input: impl BufRead, mut output: impl Write, format: BatchFormat) -> io::Result<BatchReport> {
+    let mut report = BatchReport::default();
+
+    if format == BatchFormat::Csv {
+        writeln!(output, "query,word,pronunciation,concise_definition,definitions")?;
+    }
+
+    for (number, line) in input.lines().enumerate() {
+        let line = line?;
+        let word = line.trim();
+        if word.is_empty() || word.starts_with('#') {
+            continue;
+        }
+
+        let result = dictionary.lookup(word).map_err(|e| io::Error::other(e.to_string()))?;
+        let Some(result) = result else {
+            report.not_found.push(NotFound {
+                line: number + 1,
+                word: word.to_string(),
+                suggestions: dictionary.suggest_similar(word, 3).unwrap_or_default(),
+            });
+            continue;
+        };
+
+        report.found += 1;
+        let definition = &result.definition;
+        match format {
+            BatchFormat::Jsonl => {
+                let record = BatchRecord {
+                    query: word,
+                    source: &result.source,
+                    inflection: result.inflection.as_ref(),
+                    entry: definition,
+                };
+                serde_json::to_writer(&mut output, &record)?;
+                writeln!(output)?;
+            }
+            BatchFormat::Csv => {
+                let meanings: Vec<String> = definition
+                    .meanings
+                    .iter()
+                    .flatten()
+                    .enumerate()
+                    .map(|(i, meaning)| match &meaning.explanation_cn {
+                        Some(cn) => format!("{}. {} {} {}", i + 1, meaning.part_of_speech, meaning.explanation_en, cn),
+                        None => format!("{}. {} {}", i + 1, meaning.part_of_speech, meaning.explanation_en),
+                    })
+                    .collect();
+                let fields = [
+                    word,
+                    &definition.word,
+                    definition.phonetic.as_deref().unwrap_or(""),
+                    definition.concise_definition.as_deref().unwrap_or(""),
+                    &meanings.join("\n"),
+                ];
+                let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+                writeln!(output, "{}", row.join(","))?;
+            }
+            BatchFormat::Markdown => {
+                write!(output, "{}", render::markdown(definition, result.inflection.as_ref()))?;
+                writeln!(output)?;
+            }
+        }
+    }
+
+    output.flush()?;
+    Ok(report)
+}
+
+// 未找到的单词清单，每行：行号、单词和最接近的词头
+pub fn write_not_found_report(report: &BatchReport, mut output: impl Write) -> io::Result<()> {
+    for missing in &report.not_found {
+        if missing.suggestions.is_empty() {
+            writeln!(output, "{}\t{}", missing.line, missing.word)?;
+        } else {
+            writeln!(output, "{}\t{}\tdid you mean: {}", missing.line, missing.word, missing.suggestions.join(", "))?;
+        }
+    }
+    output.flush()
+}
+
+// RFC 4180：包含逗号、引号或换行时加引号，引号写两次
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::Dictionary;
+
+    #[test]
+    fn csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("give"), "give");
+        assert_eq!(csv_field(""), "");
+        assert_eq!(csv_field("give, hand"), "\"give, hand\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("1. one\n2. two"), "\"1. one\n2. two\"");
+        assert_eq!(csv_field("a\r\nb"), "\"a\r\nb\"");
+        assert_eq!(csv_field("放弃；抛弃"), "放弃；抛弃");
+    }
+
+    fn dictionary(temp: &tempfile::TempDir) -> DictionarySet {
+        let words_dir = temp.path().join("words");
+        std::fs::create_dir_all(&words_dir).unwrap();
+        let give = serde_json::json!({
+            "word": "give",
+            "pronunciation": "/ɡɪv/",
+            "concise_definition": "v. 给, 赠送",
+            "forms": { "past_tense": "gave" },
+            "definitions": [
+                { "pos": "verb", "explanation_en": "To hand something to someone, \"freely\".", "explanation_cn": "给" },
+                { "pos": "noun", "explanation_en": "Elasticity" }
+            ]
+        });
+        std::fs::write(words_dir.join("give.json"), give.to_string()).unwrap();
+        let dictionary = Dictionary::with_index_path(
+            words_dir.to_string_lossy().into_owned(),
+            temp.path().join("index").to_string_lossy().into_owned(),
+        );
+        tokio::runtime::Runtime::new().unwrap().block_on(dictionary.rebuild_index_async()).unwrap();
+        DictionarySet::single(dictionary)
+    }
+
+    const INPUT: &str = "# word list\ngive\n\ngave\ngvie\nzzzz\n";
+
+    fn run(format: BatchFormat) -> (String, BatchReport) {
+        let temp = tempfile::tempdir().unwrap();
+        let dictionary = dictionary(&temp);
+        let mut output = Vec::new();
+        let report = run_batch(&dictionary, INPUT.as_bytes(), &mut output, format).unwrap();
+        (String::from_utf8(output).unwrap(), report)
+    }
+
+    #[test]
+    fn reports_found_and_not_found_words() {
+        let (_, report) = run(BatchFormat::Jsonl);
+        assert_eq!(report.found, 2);
+        let missing: Vec<(usize, &str, Vec<String>)> = report
+            .not_found
+            .iter()
+            .map(|missing| (missing.line, missing.word.as_str(), missing.suggestions.clone()))
+            .collect();
+        assert_eq!(missing, [(5, "gvie", vec!["give".to_string()]), (6, "zzzz", vec![])]);
+
+        let mut text = Vec::new();
+        write_not_found_report(&report, &mut text).unwrap();
+        assert_eq!(String::from_utf8(text).unwrap(), "5\tgvie\tdid you mean: give\n6\tzzzz\n");
+    }
+
+    #[test]
+    fn jsonl_records_carry_query_source_and_inflection() {
+        let (output, _) = run(BatchFormat::Jsonl);
+        let records: Vec<serde_json::Value> = output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["query"], "give");
+        assert_eq!(records[0]["source"], "default");
+        assert_eq!(records[0]["entry"]["word"], "give");
+        assert!(records[0].get("inflection").is_none());
+        assert_eq!(records[1]["query"], "gave");
+        assert_eq!(records[1]["inflection"]["kinds"], serde_json::json!(["past_tense"]));
+        assert_eq!(records[1]["entry"]["word"], "give");
+    }
+
+    #[test]
+    fn csv_rows_escape_commas_quotes_and_newlines() {
+        let (output, _) = run(BatchFormat::Csv);
+        let definitions = "\"1. verb To hand something to someone, \"\"freely\"\". 给\n2. noun Elasticity\"";
+        let row = |query: &str| format!("{},give,/ɡɪv/,\"v. 给, 赠送\",{}\n", query, definitions);
+        assert_eq!(
+            output,
+            format!("query,word,pronunciation,concise_definition,definitions\n{}{}", row("give"), row("gave"))
+        );
+    }
+
+    #[test]
+    fn markdown_mentions_the_inflection() {
+        let (output, _) = run(BatchFormat::Markdown);
+        assert!(output.contains("give"));
+        assert!(output.contains("past tense"));
+    }
+}
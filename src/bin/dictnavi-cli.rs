@@ -0,0 +1,592 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use clap::{Parser, Subcommand};
+use serde_json::json;
+
+use DictNavi::batch::{self, BatchFormat};
+use DictNavi::cjk;
+use DictNavi::config::Config;
+use DictNavi::dict_server;
+use DictNavi::dictionary_set::DictionarySet;
+use DictNavi::import;
+use DictNavi::pack;
+use DictNavi::render;
+use DictNavi::server;
+use DictNavi::tui::DictNaviTui;
+use DictNavi::validate;
+use DictNavi::watcher::WordsWatcher;
+
+// 退出码：0 成功，1 未找到，2 出错（与 clap 的参数错误一致），
+// 3 索引已构建并提交、但有文件因错误被跳过
+const EXIT_NOT_FOUND: u8 = 1;
+const EXIT_ERROR: u8 = 2;
+const EXIT_PARTIAL: u8 = 3;
+
+const EXIT_STATUS_HELP: &str = "\
+Exit status:
+  0  success
+  1  the word or prefix was not found (lookup, search, batch)
+  2  error, or validate found errors (and warnings with --strict)
+  3  build-index built the index but skipped files that could not be indexed";
+
+type CliResult = Result<ExitCode, Box<dyn std::error::Error>>;
+
+/// Look up words in the DictNavi dictionary from the command line
+#[derive(Parser)]
+#[command(name = "dictnavi-cli", version, after_help = EXIT_STATUS_HELP)]
+struct Cli {
+    /// Directory containing the word JSON files (overrides the config file)
+    #[arg(long, global = true)]
+    words_dir: Option<PathBuf>,
+
+    /// Writable directory for the index and caches (overrides the config file)
+    #[arg(long, global = true)]
+    index_dir: Option<PathBuf>,
+
+    /// Config file to use instead of <config dir>/dictnavi/config.toml
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Memory budget of the index writer in MB, at least 15 (overrides the config file)
+    #[arg(long, global = true)]
+    writer_memory_mb: Option<usize>,
+
+    /// Threads used to read and parse word files while indexing (overrides the config file)
+    #[arg(long, global = true)]
+    index_threads: Option<usize>,
+
+    /// Print JSON instead of plain text
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Bring an out-of-date index up to date before looking words up (otherwise only a missing index is built)
+    #[arg(long, global = true)]
+    sync: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show the definition of a word; inflected forms resolve to their lemma
+    Lookup {
+        /// The word or phrase to look up, e.g. `lookup give up`
+        #[arg(required = true, num_args = 1..)]
+        word: Vec<String>,
+    },
+    /// List headwords starting with a prefix, with their concise definitions
+    Search {
+        prefix: String,
+        /// Maximum number of results
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Bring the index up to date with the words directory
+    BuildIndex {
+        /// Rebuild the whole index instead of syncing changed files only
+        #[arg(long)]
+        full: bool,
+    },
+    /// Show the number of entries and the size of the index
+    Stats,
+    /// Look up every word of a word list (one per line) and write all definitions
+    Batch {
+        /// Word list file; reads stdin when omitted or `-`
+        input: Option<PathBuf>,
+        /// Output format: jsonl, csv or markdown
+        #[arg(long, default_value_t = BatchFormat::Jsonl)]
+        format: BatchFormat,
+        /// Write the definitions to this file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        /// Write the words that were not found to this file instead of stderr
+        #[arg(long)]
+        not_found: Option<PathBuf>,
+    },
+    /// Convert a StarDict (.ifo/.idx/.dict.dz), MDict (.mdx) or JSONL pack dictionary into word JSON files
+    Import {
+        /// The .ifo, .mdx or .jsonl[.gz|.zst] file, or a directory containing an .ifo file
+        source: PathBuf,
+        /// Directory to write the word files to; defaults to the words directory
+        #[arg(long)]
+        to: Option<PathBuf>,
+        /// Replace word files that already exist
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Write the word JSON files of a directory into a single JSONL pack (.jsonl, .jsonl.gz or .jsonl.zst)
+    Pack {
+        /// Pack file to create; the extension selects the compression
+        output: PathBuf,
+        /// Directory to read the word files from; defaults to the words directory
+        #[arg(long)]
+        from: Option<PathBuf>,
+    },
+    /// Check the word files against the data format and content rules without indexing them
+    Validate {
+        /// Words directory or pack to check; defaults to every configured dictionary
+        source: Option<PathBuf>,
+        /// Also exit with an error status when there are only warnings
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Interactive prompt: type a word to look it up, 'quit' to exit
+    Repl,
+    /// Full-screen terminal UI with live suggestions and recent history
+    Tui,
+    /// Serve the dictionary as a JSON HTTP API
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        addr: SocketAddr,
+    },
+    /// Serve the dictionary over the DICT protocol (RFC 2229) for dict, GoldenDict and other clients
+    DictServer {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:2628")]
+        addr: SocketAddr,
+    },
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let mut config = match Config::load(cli.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(EXIT_ERROR);
+        }
+    };
+    config.writer_memory_mb = cli.writer_memory_mb.or(config.writer_memory_mb);
+    config.index_threads = cli.index_threads.or(config.index_threads);
+    if let Err(e) = config.check_build_settings() {
+        eprintln!("error: {}", e);
+        return ExitCode::from(EXIT_ERROR);
+    }
+    let words_dir = config.locations(cli.words_dir.clone(), cli.index_dir.clone()).words_dir;
+    let dictionary = config.dictionary_set(cli.words_dir, cli.index_dir);
+
+    let result = match cli.command {
+        Command::Lookup { word } => lookup(&dictionary, &word.join(" "), cli.sync, cli.json).await,
+        Command::Search { prefix, limit } => search(&dictionary, &prefix, limit, cli.sync, cli.json).await,
+        Command::BuildIndex { full } => build_index(&dictionary, full, cli.json).await,
+        Command::Stats => stats(&dictionary, cli.sync, cli.json).await,
+        Command::Batch { input, format, output, not_found } => {
+            batch(&dictionary, input, format, output, not_found, cli.sync).await
+        }
+        Command::Import { source, to, overwrite } => {
+            import(&dictionary, &source, &to.unwrap_or(words_dir), overwrite, cli.json).await
+        }
+        Command::Pack { output, from } => pack(&from.unwrap_or(words_dir), &output, cli.json),
+        Command::Validate { source, strict } => validate(&dictionary, source, strict, cli.json),
+        Command::Repl => repl(&dictionary, cli.sync).await,
+        Command::Tui => tui(dictionary, cli.sync).await,
+        Command::Serve { addr } => serve(dictionary, addr, Protocol::Http, cli.sync).await,
+        Command::DictServer { addr } => serve(dictionary, addr, Protocol::Dict, cli.sync).await,
+    };
+
+    match result {
+        Ok(code) => code,
+        // 输出管道被关闭（例如 `dictnavi-cli --json validate | head`）时正常退出
+        Err(e) if is_broken_pipe(e.as_ref()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
+}
+
+fn is_broken_pipe(error: &(dyn std::error::Error + 'static)) -> bool {
+    error.downcast_ref::<io::Error>().is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe)
+}
+
+// 查询前只在索引缺失时构建索引。索引过期时默认只提示运行 build-index，不写索引：
+// 脚本中反复调用的 lookup 不会每次都同步，索引目录只读时也能查询；--sync 时先同步
+async fn ensure_index(dictionary: &DictionarySet, sync: bool) -> Result<(), Box<dyn std::error::Error>> {
+    for source in dictionary.sources() {
+        if !source.index_exists()? {
+            eprintln!("Index is missing, building {} ...", source.words_directory());
+            source.sync_index_async().await?;
+        } else if source.needs_index_build()? {
+            if sync {
+                eprintln!("Index is out of date, syncing {} ...", source.words_directory());
+                source.sync_index_async().await?;
+            } else {
+                eprintln!(
+                    "Warning: the index of {} is out of date; run `dictnavi-cli build-index` or pass --sync",
+                    source.words_directory()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn lookup(dictionary: &DictionarySet, word: &str, sync: bool, json: bool) -> CliResult {
+    ensure_index(dictionary, sync).await?;
+
+    let mut out = io::stdout().lock();
+    match dictionary.lookup(word)? {
+        Some(result) => {
+            if json {
+                writeln!(out, "{}", serde_json::to_string_pretty(&result.definition)?)?;
+            } else {
+                if dictionary.has_multiple_sources() {
+                    writeln!(out, "[{}]", result.source)?;
+                }
+                if let Some(inflection) = &result.inflection {
+                    writeln!(out, "({})", inflection.describe())?;
+                }
+                write!(out, "{}", render::plain_text(&result.definition))?;
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        None => {
+            let suggestions = dictionary.suggest_similar(word, 5)?;
+            if json {
+                writeln!(out, "{}", json!({ "error": "not_found", "word": word, "suggestions": suggestions }))?;
+            } else {
+                eprintln!("Word not found: {}", word);
+                if !suggestions.is_empty() {
+                    eprintln!("Did you mean: {}", suggestions.join(", "));
+                }
+            }
+            Ok(ExitCode::from(EXIT_NOT_FOUND))
+        }
+    }
+}
+
+async fn search(dictionary: &DictionarySet, prefix: &str, limit: usize, sync: bool, json: bool) -> CliResult {
+    ensure_index(dictionary, sync).await?;
+
+    let mut out = io::stdout().lock();
+    let results = dictionary.search_words(prefix, limit)?;
+    if json {
+        let items: Vec<_> = results
+            .iter()
+            .map(|(word, concise)| json!({ "word": word, "concise_definition": concise }))
+            .collect();
+        writeln!(out, "{}", serde_json::to_string_pretty(&items)?)?;
+    } else {
+        for (word, concise) in &results {
+            writeln!(out, "{}\t{}", word, concise)?;
+        }
+    }
+
+    if results.is_empty() {
+        Ok(ExitCode::from(EXIT_NOT_FOUND))
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+async fn build_index(dictionary: &DictionarySet, full: bool, json: bool) -> CliResult {
+    let summary = if full {
+        dictionary.rebuild_index_async().await?
+    } else {
+        dictionary.sync_index_async().await?
+    };
+
+    let mut out = io::stdout().lock();
+    if json {
+        writeln!(out, "{}", serde_json::to_string_pretty(&summary)?)?;
+    } else {
+        writeln!(
+            out,
+            "{}: {} added, {} updated, {} removed, {} unchanged, {} errors ({} files, {:.1}s)",
+            if summary.rebuilt { "Index rebuilt" } else { "Index synced" },
+            summary.added,
+            summary.updated,
+            summary.removed,
+            summary.unchanged,
+            summary.errors,
+            summary.total_files,
+            summary.elapsed_ms as f64 / 1000.0,
+        )?;
+        for issue in &summary.issues {
+            writeln!(out, "  {}: {}: {}", issue.kind.label(), issue.location(), issue.message)?;
+        }
+        // 按首字母等分目录存放时列出每个目录中的文件数
+        if summary.directories.len() > 1 {
+            for (directory, count) in &summary.directories {
+                writeln!(out, "  {:<24} {} files", directory, count)?;
+            }
+        }
+    }
+
+    // 跳过的文件已在报告中列出，索引本身已经更新，不算失败
+    if summary.errors > 0 {
+        Ok(ExitCode::from(EXIT_PARTIAL))
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+async fn stats(dictionary: &DictionarySet, sync: bool, json: bool) -> CliResult {
+    ensure_index(dictionary, sync).await?;
+
+    let mut out = io::stdout().lock();
+    let stats = dictionary.stats()?;
+    if json {
+        // 只有一个词典时输出单个对象，与之前的格式一致
+        match stats.as_slice() {
+            [stats] => writeln!(out, "{}", serde_json::to_string_pretty(stats)?)?,
+            _ => writeln!(out, "{}", serde_json::to_string_pretty(&stats)?)?,
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    for (i, stats) in stats.iter().enumerate() {
+        if dictionary.has_multiple_sources() {
+            if i > 0 {
+                writeln!(out)?;
+            }
+            let state = if dictionary.is_enabled(&stats.name) { "" } else { " (disabled)" };
+            writeln!(out, "Dictionary:      {}{}", stats.name, state)?;
+        }
+        writeln!(out, "Words directory: {}", stats.words_directory)?;
+        writeln!(out, "Index:           {}", stats.index_path)?;
+        writeln!(out, "Entries:         {}", stats.entries)?;
+        writeln!(out, "Indexed files:   {}", stats.indexed_files)?;
+        writeln!(out, "Segments:        {}", stats.segments)?;
+        writeln!(out, "Index size:      {:.1} MiB", stats.index_size_bytes as f64 / (1024.0 * 1024.0))?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn batch(
+    dictionary: &DictionarySet,
+    input: Option<PathBuf>,
+    format: BatchFormat,
+    output: Option<PathBuf>,
+    not_found: Option<PathBuf>,
+    sync: bool,
+) -> CliResult {
+    ensure_index(dictionary, sync).await?;
+
+    let input: Box<dyn BufRead> = match input {
+        Some(path) if path.as_os_str() != "-" => Box::new(BufReader::new(File::open(path)?)),
+        _ => Box::new(io::stdin().lock()),
+    };
+    let output: Box<dyn Write> = match output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(io::stdout().lock()),
+    };
+
+    let report = batch::run_batch(dictionary, input, output, format)?;
+
+    match not_found {
+        Some(path) => batch::write_not_found_report(&report, BufWriter::new(File::create(path)?))?,
+        None if !report.not_found.is_empty() => {
+            eprintln!("Not found ({}):", report.not_found.len());
+            batch::write_not_found_report(&report, io::stderr().lock())?;
+        }
+        None => {}
+    }
+    eprintln!("{} found, {} not found", report.found, report.not_found.len());
+
+    if report.not_found.is_empty() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::from(EXIT_NOT_FOUND))
+    }
+}
+
+async fn import(dictionary: &DictionarySet, source: &Path, words_dir: &Path, overwrite: bool, json: bool) -> CliResult {
+    let summary = import::import_file(source, words_dir, overwrite)?;
+    let mut out = io::stdout().lock();
+    if json {
+        writeln!(out, "{}", serde_json::to_string_pretty(&summary)?)?;
+    } else {
+        writeln!(
+            out,
+            "Imported {} entries into {}: {} files written, {} merged, {} renamed, {} existing files skipped",
+            summary.entries,
+            words_dir.display(),
+            summary.written,
+            summary.merged,
+            summary.renamed,
+            summary.skipped_existing,
+        )?;
+    }
+
+    // 导入到某个词典的 words 目录时同步它的索引
+    let target = words_dir.canonicalize()?;
+    for source in dictionary.sources() {
+        if Path::new(source.words_directory()).canonicalize().ok().as_ref() == Some(&target) {
+            source.sync_index_async().await?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn pack(words_dir: &Path, output: &Path, json: bool) -> CliResult {
+    if !pack::is_pack(output) {
+        return Err(format!("{} is not a pack file name (use .jsonl, .jsonl.gz or .jsonl.zst)", output.display()).into());
+    }
+    let summary = pack::pack_directory(words_dir, output)?;
+    let mut out = io::stdout().lock();
+    if json {
+        writeln!(out, "{}", serde_json::to_string_pretty(&summary)?)?;
+    } else {
+        writeln!(
+            out,
+            "Packed {} entries from {} into {} ({} files skipped)",
+            summary.entries,
+            words_dir.display(),
+            output.display(),
+            summary.errors,
+        )?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn validate(dictionary: &DictionarySet, source: Option<PathBuf>, strict: bool, json: bool) -> CliResult {
+    let sources: Vec<PathBuf> = match source {
+        Some(source) => vec![source],
+        None => dictionary
+            .sources()
+            .iter()
+            .map(|source| PathBuf::from(source.words_directory()))
+            .collect(),
+    };
+
+    let mut reports = Vec::new();
+    for source in &sources {
+        reports.push((source, validate::validate(source)?));
+    }
+
+    let mut out = io::stdout().lock();
+    if json {
+        // 只检查一个来源时输出单个报告
+        match reports.as_slice() {
+            [(_, report)] => writeln!(out, "{}", serde_json::to_string_pretty(report)?)?,
+            _ => {
+                let reports: Vec<_> = reports
+                    .iter()
+                    .map(|(source, report)| json!({ "source": source, "report": report }))
+                    .collect();
+                writeln!(out, "{}", serde_json::to_string_pretty(&reports)?)?;
+            }
+        }
+    } else {
+        for (source, report) in &reports {
+            for issue in &report.issues {
+                writeln!(out, "{}", issue.describe())?;
+            }
+            writeln!(
+                out,
+                "{}: {} entries checked, {} errors, {} warnings",
+                source.display(),
+                report.entries,
+                report.errors,
+                report.warnings,
+            )?;
+        }
+    }
+
+    let errors: usize = reports.iter().map(|(_, report)| report.errors).sum();
+    let warnings: usize = reports.iter().map(|(_, report)| report.warnings).sum();
+    if errors > 0 || (strict && warnings > 0) {
+        Ok(ExitCode::from(EXIT_ERROR))
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+async fn repl(dictionary: &DictionarySet, sync: bool) -> CliResult {
+    ensure_index(dictionary, sync).await?;
+
+    let stdin = io::stdin();
+    let mut out = io::stdout().lock();
+    let mut line = String::new();
+    loop {
+        write!(out, "> ")?;
+        out.flush()?;
+
+        line.clear();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            break;
+        }
+        let word = line.trim();
+        match word {
+            "" => continue,
+            "quit" | "exit" => break,
+            _ => {}
+        }
+
+        if cjk::contains_cjk(word) {
+            let results = dictionary.reverse_search(word, 20)?;
+            if results.is_empty() {
+                writeln!(out, "No English words found for '{}'", word)?;
+            }
+            for (english, concise) in results {
+                writeln!(out, "{}\t{}", english, concise)?;
+            }
+            continue;
+        }
+
+        match dictionary.lookup(word)? {
+            Some(result) => {
+                if dictionary.has_multiple_sources() {
+                    writeln!(out, "[{}]", result.source)?;
+                }
+                if let Some(inflection) = &result.inflection {
+                    writeln!(out, "({})", inflection.describe())?;
+                }
+                write!(out, "{}", render::plain_text(&result.definition))?;
+            }
+            None => {
+                writeln!(out, "Word '{}' not found", word)?;
+                let suggestions = dictionary.suggest_similar(word, 5)?;
+                if !suggestions.is_empty() {
+                    writeln!(out, "Did you mean: {}", suggestions.join(", "))?;
+                }
+            }
+        }
+        writeln!(out)?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn tui(dictionary: DictionarySet, sync: bool) -> CliResult {
+    ensure_index(&dictionary, sync).await?;
+    DictNaviTui::new(Arc::new(dictionary)).run()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+enum Protocol {
+    Http,
+    Dict,
+}
+
+async fn serve(dictionary: DictionarySet, addr: SocketAddr, protocol: Protocol, sync: bool) -> CliResult {
+    ensure_index(&dictionary, sync).await?;
+
+    let dictionary = Arc::new(dictionary);
+    // 与图形界面一致：每个词典的 words 目录中的文件变化后自动重新索引
+    let mut watchers = Vec::new();
+    for source in dictionary.sources() {
+        let name = source.name().to_string();
+        watchers.push(WordsWatcher::start(Arc::clone(source), move |result| match result {
+            Ok(summary) if !summary.changed_words.is_empty() => {
+                eprintln!("Reindexed {}: {}", name, summary.changed_words.join(", "))
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("Reindex of {} failed: {}", name, e),
+        })?);
+    }
+
+    match protocol {
+        Protocol::Http => server::serve(dictionary, addr).await?,
+        Protocol::Dict => dict_server::serve(dictionary, addr).await?,
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,180 @@
+use tantivy::tokenizer::{Token, TokenStream, Tokenizer};
+
+// 注册到 tantivy 索引上的分词器名称
+pub const CJK_TOKENIZER: &str = "cjk";
+
+// 中文分词器：汉字按单字和相邻二字（bigram）切分，其他字母数字按单词切分并转小写。
+// 不依赖词典，“放弃”既能匹配“放弃”也能匹配“抛弃, 遗弃, 放弃”中的同一片段。
+#[derive(Clone, Default)]
+pub struct CjkTokenizer;
+
+pub struct CjkTokenStream {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl Tokenizer for CjkTokenizer {
+    type TokenStream<'a> = CjkTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> CjkTokenStream {
+        CjkTokenStream {
+            tokens: tokenize(text, true),
+            index: 0,
+        }
+    }
+}
+
+impl TokenStream for CjkTokenStream {
+    fn advance(&mut self) -> bool {
+        if self.index < self.tokens.len() {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.index - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.index - 1]
+    }
+}
+
+// 判断字符是否为汉字（CJK 统一表意文字及扩展区、兼容表意文字）
+pub fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xF900..=0xFAFF
+        | 0x20000..=0x2A6DF
+        | 0x2A700..=0x2EBEF
+        | 0x30000..=0x3134F)
+}
+
+pub fn contains_cjk(text: &str) -> bool {
+    text.chars().any(is_cjk)
+}
+
+// 查询词项：两个字以上的汉字串只用 bigram（避免单字匹配过多），单个汉字用单字
+pub fn query_terms(text: &str) -> Vec<String> {
+    let mut terms: Vec<String> = tokenize(text, false).into_iter().map(|token| token.text).collect();
+    terms.sort();
+    terms.dedup();
+    terms
+}
+
+// 切分文本；with_unigrams 为 true 时（建索引）每个汉字也作为单独的词项
+fn tokenize(text: &str, with_unigrams: bool) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (start, c) = chars[i];
+
+        if is_cjk(c) {
+            // 连续的汉字串
+            let mut end = i;
+            while end < chars.len() && is_cjk(chars[end].1) {
+                end += 1;
+            }
+            let run = &chars[i..end];
+
+            for (k, &(offset, ch)) in run.iter().enumerate() {
+                let offset_to = offset + ch.len_utf8();
+                if with_unigrams || run.len() == 1 {
+                    push_token(&mut tokens, ch.to_string(), offset, offset_to);
+                }
+                if let Some(&(next_offset, next)) = run.get(k + 1) {
+                    push_token(&mut tokens, format!("{}{}", ch, next), offset, next_offset + next.len_utf8());
+                }
+            }
+            i = end;
+        } else if c.is_alphanumeric() {
+            // 非汉字的字母数字串作为一个单词
+            let mut end = i;
+            while end < chars.len() && chars[end].1.is_alphanumeric() && !is_cjk(chars[end].1) {
+                end += 1;
+            }
+            let offset_to = chars.get(end).map(|&(offset, _)| offset).unwrap_or(text.len());
+            push_token(&mut tokens, text[start..offset_to].to_lowercase(), start, offset_to);
+            i = end;
+        } else {
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+fn push_token(tokens: &mut Vec<Token>, text: String, offset_from: usize, offset_to: usize) {
+    let position = tokens.len();
+    tokens.push(Token {
+        offset_from,
+        offset_to,
+        position,
+        text,
+        position_length: 1,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(tokens: &[Token]) -> Vec<&str> {
+        tokens.iter().map(|token| token.text.as_str()).collect()
+    }
+
+    #[test]
+    fn indexing_emits_unigrams_and_bigrams() {
+        let tokens = tokenize("放弃希望", true);
+        assert_eq!(texts(&tokens), ["放", "放弃", "弃", "弃希", "希", "希望", "望"]);
+        // 偏移是字节区间，bigram 覆盖两个字
+        assert_eq!((tokens[1].offset_from, tokens[1].offset_to), (0, 6));
+        assert_eq!(tokens.iter().map(|token| token.position).collect::<Vec<_>>(), [0, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn querying_uses_bigrams_except_for_single_characters() {
+        assert_eq!(query_terms("放弃"), ["放弃"]);
+        assert_eq!(query_terms("放弃希望"), ["希望", "弃希", "放弃"]);
+        assert_eq!(query_terms("弃"), ["弃"]);
+        // 排序并去重
+        assert_eq!(query_terms("放弃 放弃"), ["放弃"]);
+    }
+
+    #[test]
+    fn latin_words_are_lowercased_whole_words() {
+        let tokens = tokenize("Give UP 2024", true);
+        assert_eq!(texts(&tokens), ["give", "up", "2024"]);
+        assert_eq!((tokens[1].offset_from, tokens[1].offset_to), (5, 7));
+    }
+
+    #[test]
+    fn mixed_latin_and_cjk_are_split_at_the_boundary() {
+        assert_eq!(texts(&tokenize("DNA检测", true)), ["dna", "检", "检测", "测"]);
+        assert_eq!(query_terms("abc放弃def"), ["abc", "def", "放弃"]);
+    }
+
+    #[test]
+    fn punctuation_separates_tokens() {
+        // 全角和半角标点都不产生词项，也不跨标点组成 bigram
+        assert_eq!(texts(&tokenize("抛弃，遗弃; rock'n'roll!", true)), ["抛", "抛弃", "弃", "遗", "遗弃", "弃", "rock", "n", "roll"]);
+        assert!(tokenize("，。！？ ...", true).is_empty());
+        assert!(query_terms("").is_empty());
+    }
+
+    #[test]
+    fn detects_cjk_characters() {
+        assert!(is_cjk('放'));
+        assert!(is_cjk('\u{20000}'));
+        assert!(!is_cjk('a'));
+        assert!(!is_cjk('，'));
+        assert!(contains_cjk("give 放弃"));
+        assert!(!contains_cjk("give up"));
+    }
+}
@@ -0,0 +1,271 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::dictionary::{BuildSettings, Dictionary, DEFAULT_DICTIONARY_NAME, DEFAULT_WRITER_MEMORY, MIN_WRITER_MEMORY};
+use crate::dictionary_set::DictionarySet;
+use crate::pack;
+
+// 配置文件：$XDG_CONFIG_HOME/dictnavi/config.toml（Windows 与 macOS 上为对应的配置目录）
+pub const CONFIG_FILE: &str = "config.toml";
+const APP_DIR: &str = "dictnavi";
+
+// 默认的 words 目录名与索引目录名
+const WORDS_DIR: &str = "words";
+const INDEX_DIR: &str = ".index";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    // 词条 JSON 文件所在目录；可以只读
+    pub words_dir: Option<PathBuf>,
+    // 索引和缓存目录；必须可写，不设置时使用 words 目录下的 .index 或用户缓存目录
+    pub index_dir: Option<PathBuf>,
+    // 主词典的名称（多个词典时显示为结果来源），默认为 "default"
+    pub name: Option<String>,
+    // 构建索引时 IndexWriter 的内存预算（MB），至少 15，默认 50；预算越大 tantivy 使用的索引线程越多
+    pub writer_memory_mb: Option<usize>,
+    // 构建索引时读取和解析词条的线程数，默认为 CPU 核数
+    pub index_threads: Option<usize>,
+    // 与主词典同时加载的其他词典（[[dictionaries]]）
+    pub dictionaries: Vec<SourceConfig>,
+}
+
+// 附加词典：名称、words 目录、可选的索引目录、优先级（主词典为 0，越大越优先）和是否启用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SourceConfig {
+    pub name: String,
+    pub words_dir: PathBuf,
+    #[serde(default)]
+    pub index_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+// 最终使用的 words 目录与索引目录
+#[derive(Debug, Clone)]
+pub struct Locations {
+    pub words_dir: PathBuf,
+    pub index_dir: PathBuf,
+}
+
+impl Config {
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join(APP_DIR).join(CONFIG_FILE))
+    }
+
+    // 读取配置文件；未指定 path 时读取默认位置，默认位置的文件不存在时返回空配置
+    pub fn load(path: Option<&Path>) -> Result<Self, String> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match Self::default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Config::default()),
+            },
+        };
+
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if !required && e.kind() == std::io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(e) => return Err(format!("Failed to read config file {}: {}", path.display(), e)),
+        };
+        let mut config: Config =
+            toml::from_str(&text).map_err(|e| format!("Invalid config file {}: {}", path.display(), e))?;
+
+        // 配置文件中的相对路径相对于配置文件所在目录
+        let base = path.parent().unwrap_or(Path::new("."));
+        config.words_dir = config.words_dir.map(|dir| resolve_path(base, &dir));
+        config.index_dir = config.index_dir.map(|dir| resolve_path(base, &dir));
+        for source in &mut config.dictionaries {
+            source.words_dir = resolve_path(base, &source.words_dir);
+            source.index_dir = source.index_dir.as_ref().map(|dir| resolve_path(base, dir));
+        }
+
+        // 词典名称用于区分结果来源和界面中的开关，不能重复
+        let mut names = vec![config.main_name()];
+        for source in &config.dictionaries {
+            if names.contains(&source.name.as_str()) {
+                return Err(format!("Invalid config file {}: duplicate dictionary name '{}'", path.display(), source.name));
+            }
+            names.push(&source.name);
+        }
+
+        config
+            .check_build_settings()
+            .map_err(|e| format!("Invalid config file {}: {}", path.display(), e))?;
+        Ok(config)
+    }
+
+    // 内存预算低于 tantivy 的下限时无法创建 IndexWriter
+    pub fn check_build_settings(&self) -> Result<(), String> {
+        let min_mb = MIN_WRITER_MEMORY / 1_000_000;
+        if let Some(mb) = self.writer_memory_mb
+            && mb < min_mb
+        {
+            return Err(format!("writer_memory_mb must be at least {}, got {}", min_mb, mb));
+        }
+        if self.index_threads == Some(0) {
+            return Err("index_threads must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn build_settings(&self) -> BuildSettings {
+        BuildSettings {
+            writer_memory: self.writer_memory_mb.map_or(DEFAULT_WRITER_MEMORY, |mb| mb * 1_000_000),
+            threads: self.index_threads.unwrap_or(0),
+        }
+    }
+
+    fn main_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(DEFAULT_DICTIONARY_NAME)
+    }
+
+    // 主词典（位置同 locations）加上配置中的其他词典；配置中 enabled = false 的词典初始为禁用
+    pub fn dictionary_set(&self, words_dir: Option<PathBuf>, index_dir: Option<PathBuf>) -> DictionarySet {
+        let settings = self.build_settings();
+        let main = self
+            .locations(words_dir, index_dir)
+            .open()
+            .with_name(self.main_name())
+            .with_build_settings(settings);
+        let mut sources = vec![(main, 0)];
+        for source in &self.dictionaries {
+            let index_dir = source
+                .index_dir
+                .clone()
+                .unwrap_or_else(|| default_index_dir(&source.words_dir));
+            let locations = Locations {
+                words_dir: source.words_dir.clone(),
+                index_dir,
+            };
+            sources.push((locations.open().with_name(&source.name).with_build_settings(settings), source.priority));
+        }
+
+        let set = DictionarySet::new(sources);
+        for source in self.dictionaries.iter().filter(|source| !source.enabled) {
+            set.set_enabled(&source.name, false);
+        }
+        set
+    }
+
+    // 命令行参数优先于配置文件，都没有时使用默认位置
+    pub fn locations(&self, words_dir: Option<PathBuf>, index_dir: Option<PathBuf>) -> Locations {
+        let words_dir = words_dir
+            .or_else(|| self.words_dir.clone())
+            .unwrap_or_else(default_words_dir);
+        let index_dir = index_dir
+            .or_else(|| self.index_dir.clone())
+            .unwrap_or_else(|| default_index_dir(&words_dir));
+        Locations { words_dir, index_dir }
+    }
+}
+
+impl Locations {
+    pub fn open(&self) -> Dictionary {
+        Dictionary::with_index_path(
+            self.words_dir.to_string_lossy().into_owned(),
+            self.index_dir.to_string_lossy().into_owned(),
+        )
+    }
+}
+
+// 依次查找：当前目录下的 words，可执行文件旁的 words，用户数据目录下的 dictnavi/words；
+// 都不存在时使用当前目录下的 words（与之前的行为一致）
+fn default_words_dir() -> PathBuf {
+    let candidates = [
+        Some(PathBuf::from(WORDS_DIR)),
+        std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(|dir| dir.join(WORDS_DIR))),
+        dirs::data_dir().map(|dir| dir.join(APP_DIR).join(WORDS_DIR)),
+    ];
+
+    candidates
+        .into_iter()
+        .flatten()
+        .find(|dir| dir.is_dir())
+        .unwrap_or_else(|| PathBuf::from(WORDS_DIR))
+}
+
+// words 目录可写时索引放在 words/.index（词典包放在包文件旁的 <包文件名>.index）；
+// 否则（例如只读安装）放到用户缓存目录，按 words 目录的绝对路径区分不同的词典。
+// 这里只判断位置，不创建索引目录，索引目录在第一次构建时创建
+fn default_index_dir(words_dir: &Path) -> PathBuf {
+    let local = if pack::is_pack(words_dir) {
+        PathBuf::from(format!("{}{}", words_dir.display(), INDEX_DIR))
+    } else {
+        words_dir.join(INDEX_DIR)
+    };
+    let parent = local.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    if local.is_dir() || !words_dir.exists() || is_writable_dir(parent) {
+        return local;
+    }
+
+    let Some(cache_dir) = dirs::cache_dir() else {
+        return local;
+    };
+    let absolute = words_dir.canonicalize().unwrap_or_else(|_| words_dir.to_path_buf());
+    let digest = Sha256::digest(absolute.to_string_lossy().as_bytes());
+    let id: String = digest.iter().take(6).map(|b| format!("{:02x}", b)).collect();
+    cache_dir.join(APP_DIR).join(format!("index-{}", id))
+}
+
+// 目录是否可写：创建并立即删除一个临时文件（只读挂载、权限和 ACL 都能如实反映）
+fn is_writable_dir(dir: &Path) -> bool {
+    let probe = dir.join(format!(".dictnavi-write-test-{}", std::process::id()));
+    match fs::File::create_new(&probe) {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+// 展开开头的 ~，并把相对路径解析到 base 下
+fn resolve_path(base: &Path, path: &Path) -> PathBuf {
+    let path = match path.strip_prefix("~") {
+        Ok(rest) => match dirs::home_dir() {
+            Some(home) => home.join(rest),
+            None => path.to_path_buf(),
+        },
+        Err(_) => path.to_path_buf(),
+    };
+
+    if path.is_absolute() { path } else { base.join(path) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_index_dir_does_not_create_the_directory() {
+        let temp = tempfile::tempdir().unwrap();
+        let words_dir = temp.path().join("words");
+        fs::create_dir(&words_dir).unwrap();
+
+        assert_eq!(default_index_dir(&words_dir), words_dir.join(INDEX_DIR));
+        assert_eq!(fs::read_dir(&words_dir).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn pack_index_dir_sits_next_to_the_pack() {
+        let temp = tempfile::tempdir().unwrap();
+        let pack = temp.path().join("words.jsonl");
+        fs::write(&pack, "").unwrap();
+
+        assert_eq!(default_index_dir(&pack), temp.path().join("words.jsonl.index"));
+        assert_eq!(fs::read_dir(temp.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,490 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::dictionary::{Dictionary, LookupResult};
+use crate::dictionary_set::DictionarySet;
+use crate::render;
+
+// DICT 协议（RFC 2229）服务端，供 dict、GoldenDict 等标准客户端使用（默认端口 2628）
+
+// 只有一个词典时对外公布的数据库名与说明；有多个词典时每个启用的词典是一个数据库，以词典名称命名
+const DATABASE: &str = "dictnavi";
+const DATABASE_DESCRIPTION: &str = "DictNavi English-Chinese Dictionary";
+
+// MATCH 支持的策略；"." 表示默认策略。lev 与 dictd 相同，编辑距离为 1；
+// suggest 与查不到词时的拼写建议相同，较长的词允许编辑距离 2
+const STRATEGIES: [(&str, &str); 4] = [
+    ("exact", "Match headwords exactly"),
+    ("prefix", "Match prefixes"),
+    ("lev", "Match headwords within Levenshtein distance one"),
+    ("suggest", "Match spelling suggestions within Levenshtein distance one, or two for words longer than three letters"),
+];
+const DEFAULT_STRATEGY: &str = "lev";
+
+// 协议规定命令行最长 1024 字节；MATCH 最多返回的词头数
+const MAX_LINE_LENGTH: usize = 1024;
+const MAX_MATCHES: usize = 1000;
+
+// 在 addr 上监听，每个连接一个任务，直到进程退出
+pub async fn serve(dictionary: Arc<DictionarySet>, addr: SocketAddr) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    eprintln!("DictNavi DICT server listening on {}", listener.local_addr()?);
+
+    let connections = AtomicU64::new(0);
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let connection_id = connections.fetch_add(1, Ordering::Relaxed);
+        let dictionary = Arc::clone(&dictionary);
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(dictionary, stream, connection_id).await {
+                eprintln!("DICT connection {} closed with error: {}", peer, e);
+            }
+        });
+    }
+}
+
+// 一个连接的状态（OPTION MIME 之后每条定义前带 MIME 头）
+struct Session {
+    mime: bool,
+}
+
+async fn handle_connection(dictionary: Arc<DictionarySet>, stream: TcpStream, connection_id: u64) -> std::io::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    let banner = format!(
+        "220 dictnavi DictNavi {} <mime> <{}.{}@dictnavi>\r\n",
+        env!("CARGO_PKG_VERSION"),
+        std::process::id(),
+        connection_id
+    );
+    writer.write_all(banner.as_bytes()).await?;
+
+    let mut session = Session { mime: false };
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let read = (&mut reader).take(MAX_LINE_LENGTH as u64 + 2).read_until(b'\n', &mut line).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        if !line.ends_with(b"\n") && read > MAX_LINE_LENGTH {
+            writer.write_all(b"500 line too long\r\n").await?;
+            // 丢弃这一行剩余的内容
+            let mut rest = Vec::new();
+            reader.read_until(b'\n', &mut rest).await?;
+            continue;
+        }
+
+        let text = String::from_utf8_lossy(&line).trim().to_string();
+        if text.is_empty() {
+            continue;
+        }
+
+        let Some(args) = split_arguments(&text) else {
+            writer.write_all(b"501 syntax error, illegal parameters\r\n").await?;
+            continue;
+        };
+        let command = args.first().map(|c| c.to_ascii_uppercase()).unwrap_or_default();
+
+        match command.as_str() {
+            "QUIT" => {
+                writer.write_all(b"221 bye\r\n").await?;
+                return Ok(());
+            }
+            "OPTION" if args.len() == 2 && args[1].eq_ignore_ascii_case("MIME") => {
+                session.mime = true;
+                writer.write_all(b"250 ok - using MIME headers\r\n").await?;
+            }
+            "DEFINE" | "MATCH" => {
+                // 索引查询是阻塞操作，放到线程池执行
+                let dictionary = Arc::clone(&dictionary);
+                let mime = session.mime;
+                let is_define = command == "DEFINE";
+                let response = tokio::task::spawn_blocking(move || {
+                    let session = Session { mime };
+                    if is_define {
+                        define(&dictionary, &session, &args)
+                    } else {
+                        match_words(&dictionary, &args)
+                    }
+                })
+                .await
+                .unwrap_or_else(|e| format!("420 server temporarily unavailable: {}\r\n", e));
+                writer.write_all(response.as_bytes()).await?;
+            }
+            _ => {
+                let response = respond(&dictionary, &args);
+                writer.write_all(response.as_bytes()).await?;
+            }
+        }
+    }
+}
+
+// 不需要查询索引的命令
+fn respond(dictionary: &DictionarySet, args: &[String]) -> String {
+    let command = args[0].to_ascii_uppercase();
+    let subcommand = args.get(1).map(|s| s.to_ascii_uppercase()).unwrap_or_default();
+
+    match (command.as_str(), subcommand.as_str()) {
+        ("SHOW", "DB" | "DATABASES") => {
+            let databases = databases(dictionary);
+            if databases.is_empty() {
+                return "554 no databases present\r\n".to_string();
+            }
+            let mut response = format!("110 {} databases present\r\n", databases.len());
+            for (name, source) in &databases {
+                response.push_str(&format!("{} \"{}\"\r\n", name, quote(&description(dictionary, source))));
+            }
+            response.push_str(".\r\n250 ok\r\n");
+            response
+        }
+        ("SHOW", "STRAT" | "STRATEGIES") => {
+            let mut response = format!("111 {} strategies available\r\n", STRATEGIES.len());
+            for (name, description) in STRATEGIES {
+                response.push_str(&format!("{} \"{}\"\r\n", name, description));
+            }
+            response.push_str(".\r\n250 ok\r\n");
+            response
+        }
+        ("SHOW", "INFO") => match args.get(2) {
+            Some(database) => match databases(dictionary).into_iter().find(|(name, _)| name == database) {
+                Some((_, source)) => {
+                    let entries = source.stats().map(|stats| stats.entries).unwrap_or(0);
+                    let mut response = String::from("112 database information follows\r\n");
+                    response.push_str(&text_block(&format!(
+                        "{}\nWords directory: {}\nEntries: {}\n",
+                        description(dictionary, &source),
+                        source.words_directory(),
+                        entries
+                    )));
+                    response.push_str("250 ok\r\n");
+                    response
+                }
+                None => "550 invalid database, use \"SHOW DB\" for list of databases\r\n".to_string(),
+            },
+            None => "501 syntax error, illegal parameters\r\n".to_string(),
+        },
+        ("SHOW", "SERVER") => {
+            let mut response = String::from("114 server information follows\r\n");
+            response.push_str(&text_block(&format!("DictNavi {}\n", env!("CARGO_PKG_VERSION"))));
+            response.push_str("250 ok\r\n");
+            response
+        }
+        ("SHOW", _) => "501 syntax error, illegal parameters\r\n".to_string(),
+        ("CLIENT", _) => "250 ok\r\n".to_string(),
+        ("STATUS", _) => "210 status ok\r\n".to_string(),
+        ("HELP", _) => {
+            let mut response = String::from("113 help text follows\r\n");
+            response.push_str(&text_block(
+                "DEFINE database word         -- look up word in database\n\
+                 MATCH database strategy word -- match word in database using strategy\n\
+                 SHOW DB                      -- list all accessible databases\n\
+                 SHOW STRAT                   -- list available matching strategies\n\
+                 SHOW INFO database           -- provide information about the database\n\
+                 SHOW SERVER                  -- provide site-specific information\n\
+                 OPTION MIME                  -- use MIME headers\n\
+                 CLIENT info                  -- identify client to server\n\
+                 STATUS                       -- display timing information\n\
+                 HELP                         -- display this help information\n\
+                 QUIT                         -- terminate connection\n",
+            ));
+            response.push_str("250 ok\r\n");
+            response
+        }
+        ("AUTH" | "SASLAUTH" | "SASLRESP", _) => "502 command not implemented\r\n".to_string(),
+        _ => "500 unknown command\r\n".to_string(),
+    }
+}
+
+// DEFINE database word
+fn define(dictionary: &DictionarySet, session: &Session, args: &[String]) -> String {
+    let [_, database, word] = args else {
+        return "501 syntax error, illegal parameters\r\n".to_string();
+    };
+    let Some(selected) = select_databases(dictionary, database) else {
+        return "550 invalid database, use \"SHOW DB\" for list of databases\r\n".to_string();
+    };
+
+    // 每个数据库一条定义；"!" 时只取第一个有结果的数据库
+    let mut results: Vec<(String, Arc<Dictionary>, LookupResult)> = Vec::new();
+    for (name, source) in selected {
+        match source.lookup(word) {
+            Ok(Some(result)) => results.push((name, source, result)),
+            Ok(None) => continue,
+            Err(e) => return format!("420 server temporarily unavailable: {}\r\n", e),
+        }
+        if database == "!" {
+            break;
+        }
+    }
+    if results.is_empty() {
+        return "552 no match\r\n".to_string();
+    }
+
+    let mut response = format!("150 {} definitions retrieved\r\n", results.len());
+    for (name, source, result) in &results {
+        let mut body = String::new();
+        if let Some(inflection) = &result.inflection {
+            body.push_str(&format!("({})\n", inflection.describe()));
+        }
+        body.push_str(&render::plain_text(&result.definition));
+
+        response.push_str(&format!(
+            "151 \"{}\" {} \"{}\"\r\n",
+            quote(&result.definition.word),
+            name,
+            quote(&description(dictionary, source))
+        ));
+        if session.mime {
+            response.push_str("Content-type: text/plain; charset=utf-8\r\nContent-transfer-encoding: 8bit\r\n\r\n");
+        }
+        response.push_str(&text_block(&body));
+    }
+    response.push_str("250 ok\r\n");
+    response
+}
+
+// MATCH database strategy word
+fn match_words(dictionary: &DictionarySet, args: &[String]) -> String {
+    let [_, database, strategy, word] = args else {
+        return "501 syntax error, illegal parameters\r\n".to_string();
+    };
+    let Some(selected) = select_databases(dictionary, database) else {
+        return "550 invalid database, use \"SHOW DB\" for list of databases\r\n".to_string();
+    };
+
+    let strategy = match strategy.to_ascii_lowercase().as_str() {
+        "." => DEFAULT_STRATEGY.to_string(),
+        // 之前公布的策略名
+        "levenshtein" => "lev".to_string(),
+        other => other.to_string(),
+    };
+
+    if !STRATEGIES.iter().any(|(name, _)| *name == strategy) {
+        return "551 invalid strategy, use \"SHOW STRAT\" for a list of strategies\r\n".to_string();
+    }
+
+    // (数据库, 词头)；"!" 时只取第一个有结果的数据库
+    let mut matches: Vec<(String, String)> = Vec::new();
+    for (name, source) in selected {
+        let words = match strategy.as_str() {
+            "exact" => source
+                .lookup(word)
+                .map(|result| result.map(|r| r.definition.word).into_iter().collect()),
+            "prefix" => source.complete_prefix(word, MAX_MATCHES),
+            "lev" => source.similar_headwords(word, 1, MAX_MATCHES),
+            _ => source.suggest_similar(word, MAX_MATCHES),
+        };
+        let words: Vec<String> = match words {
+            Ok(words) => words,
+            Err(e) => return format!("420 server temporarily unavailable: {}\r\n", e),
+        };
+        let found = !words.is_empty();
+        matches.extend(words.into_iter().map(|word| (name.clone(), word)));
+        if database == "!" && found {
+            break;
+        }
+    }
+    if matches.is_empty() {
+        return "552 no match\r\n".to_string();
+    }
+    matches.truncate(MAX_MATCHES);
+
+    let mut response = format!("152 {} matches found\r\n", matches.len());
+    for (database, word) in &matches {
+        response.push_str(&format!("{} \"{}\"\r\n", database, quote(word)));
+    }
+    response.push_str(".\r\n250 ok\r\n");
+    response
+}
+
+// 对外公布的数据库：只有一个词典时为 dictnavi，否则为启用的各词典，按优先级排列
+fn databases(dictionary: &DictionarySet) -> Vec<(String, Arc<Dictionary>)> {
+    if !dictionary.has_multiple_sources() {
+        return dictionary
+            .enabled_sources()
+            .into_iter()
+            .map(|source| (DATABASE.to_string(), source))
+            .collect();
+    }
+    dictionary
+        .enabled_sources()
+        .into_iter()
+        .map(|source| (source.name().to_string(), source))
+        .collect()
+}
+
+fn description(dictionary: &DictionarySet, source: &Dictionary) -> String {
+    if dictionary.has_multiple_sources() {
+        format!("{} ({})", DATABASE_DESCRIPTION, source.name())
+    } else {
+        DATABASE_DESCRIPTION.to_string()
+    }
+}
+
+// "*" 表示所有数据库，"!" 表示第一个有结果的数据库；未知的数据库返回 None
+fn select_databases(dictionary: &DictionarySet, database: &str) -> Option<Vec<(String, Arc<Dictionary>)>> {
+    let databases = databases(dictionary);
+    if database == "*" || database == "!" {
+        return Some(databases);
+    }
+    let selected: Vec<_> = databases.into_iter().filter(|(name, _)| name == database).collect();
+    if selected.is_empty() { None } else { Some(selected) }
+}
+
+// 文本块：CRLF 换行，以 "." 开头的行前加一个 "."，最后以单独一行 "." 结束
+fn text_block(text: &str) -> String {
+    let mut block = String::new();
+    for line in text.lines() {
+        if line.starts_with('.') {
+            block.push('.');
+        }
+        block.push_str(line);
+        block.push_str("\r\n");
+    }
+    block.push_str(".\r\n");
+    block
+}
+
+fn quote(word: &str) -> String {
+    word.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// 按 RFC 2229 拆分命令参数：空白分隔，支持单引号、双引号和反斜杠转义；引号不配对时返回 None
+fn split_arguments(line: &str) -> Option<Vec<String>> {
+    let mut args = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let Some(&first) = chars.peek() else {
+            return Some(args);
+        };
+
+        let mut arg = String::new();
+        if first == '"' || first == '\'' {
+            chars.next();
+            loop {
+                match chars.next()? {
+                    '\\' => arg.push(chars.next()?),
+                    c if c == first => break,
+                    c => arg.push(c),
+                }
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                if c == '\\' {
+                    arg.push(chars.next()?);
+                } else {
+                    arg.push(c);
+                }
+            }
+        }
+        args.push(arg);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    #[test]
+    fn splits_plain_arguments() {
+        assert_eq!(split_arguments("DEFINE * give"), Some(strings(&["DEFINE", "*", "give"])));
+        assert_eq!(split_arguments("  MATCH   *  prefix   giv  "), Some(strings(&["MATCH", "*", "prefix", "giv"])));
+        assert_eq!(split_arguments(""), Some(strings(&[])));
+    }
+
+    #[test]
+    fn splits_quoted_arguments() {
+        assert_eq!(split_arguments("DEFINE * \"give up\""), Some(strings(&["DEFINE", "*", "give up"])));
+        assert_eq!(split_arguments("DEFINE * 'break the ice'"), Some(strings(&["DEFINE", "*", "break the ice"])));
+        // 另一种引号在引号内是普通字符
+        assert_eq!(split_arguments("DEFINE * \"rock'n'roll\""), Some(strings(&["DEFINE", "*", "rock'n'roll"])));
+        assert_eq!(split_arguments("DEFINE * \"\""), Some(strings(&["DEFINE", "*", ""])));
+    }
+
+    #[test]
+    fn splits_escaped_arguments() {
+        assert_eq!(split_arguments(r#"DEFINE * "say \"hi\"""#), Some(strings(&["DEFINE", "*", "say \"hi\""])));
+        assert_eq!(split_arguments(r"DEFINE * give\ up"), Some(strings(&["DEFINE", "*", "give up"])));
+        assert_eq!(split_arguments(r"DEFINE * back\\slash"), Some(strings(&["DEFINE", "*", r"back\slash"])));
+    }
+
+    #[test]
+    fn rejects_unbalanced_quotes() {
+        assert_eq!(split_arguments("DEFINE * \"give up"), None);
+        assert_eq!(split_arguments("DEFINE * 'give"), None);
+        assert_eq!(split_arguments("DEFINE * give\\"), None);
+    }
+
+    #[test]
+    fn quote_round_trips_through_split_arguments() {
+        let word = r#"a "quoted" \ word"#;
+        let line = format!("DEFINE * \"{}\"", quote(word));
+        assert_eq!(split_arguments(&line).unwrap()[2], word);
+    }
+
+    // 在临时目录中建立只有给定词头的词典
+    fn dictionary_with(temp: &tempfile::TempDir, words: &[&str]) -> DictionarySet {
+        let words_dir = temp.path().join("words");
+        std::fs::create_dir_all(&words_dir).unwrap();
+        for word in words {
+            let entry = format!("{{\"word\": \"{}\"}}", word);
+            std::fs::write(words_dir.join(format!("{}.json", word)), entry).unwrap();
+        }
+        let dictionary = Dictionary::with_index_path(
+            words_dir.to_string_lossy().into_owned(),
+            temp.path().join("index").to_string_lossy().into_owned(),
+        );
+        tokio::runtime::Runtime::new().unwrap().block_on(dictionary.rebuild_index_async()).unwrap();
+        DictionarySet::single(dictionary)
+    }
+
+    fn matched_words(response: &str) -> Vec<&str> {
+        response
+            .lines()
+            .filter_map(|line| line.strip_prefix("dictnavi \"")?.strip_suffix('"'))
+            .collect()
+    }
+
+    #[test]
+    fn lev_strategy_matches_within_distance_one() {
+        let temp = tempfile::tempdir().unwrap();
+        let dictionary = dictionary_with(&temp, &["banter", "bane", "bant", "band", "bandit"]);
+        let args = |strategy: &str| strings(&["MATCH", "*", strategy, "banr"]);
+
+        // 默认策略与 dictd 一致，只有一处编辑
+        for strategy in ["lev", "LEV", "levenshtein", "."] {
+            assert_eq!(matched_words(&match_words(&dictionary, &args(strategy))), ["band", "bane", "bant"]);
+        }
+        // suggest 对较长的词允许两处编辑
+        assert_eq!(
+            matched_words(&match_words(&dictionary, &args("suggest"))),
+            ["band", "bane", "bant", "banter"]
+        );
+        assert!(match_words(&dictionary, &args("soundex")).starts_with("551 "));
+    }
+
+    #[test]
+    fn text_block_stuffs_leading_dots() {
+        assert_eq!(text_block("first\n.hidden\n..two\nlast"), "first\r\n..hidden\r\n...two\r\nlast\r\n.\r\n");
+        // 单独的 "." 不能提前结束文本块
+        assert_eq!(text_block("."), "..\r\n.\r\n");
+        assert_eq!(text_block(""), ".\r\n");
+    }
+
+    #[test]
+    fn text_block_normalizes_line_endings() {
+        assert_eq!(text_block("one\r\ntwo\n"), "one\r\ntwo\r\n.\r\n");
+    }
+}
@@ -1,14 +1,27 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
-use std::sync::{Arc, RwLock};
-use std::time::SystemTime;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
 
-use crate::models::WordDefinition;
+use crate::autocomplete::Autocomplete;
+use crate::cjk::{self, CjkTokenizer, CJK_TOKENIZER};
+use crate::manifest::{self, FailedEntry, IndexManifest, ManifestEntry, PackState};
+use crate::models::{normalize_headword, Inflection, WordDefinition};
+use crate::pack;
+use crate::scan;
+use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder, DFA, SINK_STATE};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use tantivy_fst::Automaton;
+use tokio_util::sync::CancellationToken;
 use tantivy::{
     collector::TopDocs,
-    query::{QueryParser, TermQuery},
+    query::{BooleanQuery, BoostQuery, EnableScoring, Occur, Query, QueryParser, TermQuery, Weight},
     schema::*,
-    Index, Term,
+    snippet::{Snippet, SnippetGenerator},
+    DocAddress, DocSet, Index, Term,
 };
 
 // Schema 设计
@@ -18,35 +31,386 @@ fn build_schema() -> Schema {
     // 主键：单词（索引 + 存储）
     let _word = schema_builder.add_text_field("word", TEXT | STORED);
 
+    // 规范化的完整词头（小写、合并空白，不分词），用于精确匹配词组、连字符词和习语
+    let _headword = schema_builder.add_text_field("headword", STRING);
+
     // 简明释义（用于结果展示）
     let _concise_definition = schema_builder.add_text_field("concise_definition", STORED);
 
     // 整个 JSON 内容（点击时反序列化）
     let _json_data = schema_builder.add_text_field("json_data", STORED);
 
+    // 屈折形式（过去式、复数等），与 headword 相同方式规范化后索引，用于把变形解析回原形
+    let _forms = schema_builder.add_text_field("forms", STRING);
+
+    // 中文释义：简明释义和各义项的 explanation_cn，使用中文分词器索引，用于汉译英反查
+    let cjk_options = TextOptions::default().set_indexing_options(
+        TextFieldIndexing::default()
+            .set_tokenizer(CJK_TOKENIZER)
+            .set_index_option(IndexRecordOption::WithFreqs),
+    );
+    let _concise_cn = schema_builder.add_text_field("concise_cn", cjk_options.clone());
+    let _explanation_cn = schema_builder.add_text_field("explanation_cn", cjk_options);
+
+    // 全文检索：英文释义、英文例句（词干化分词，保留位置以支持短语查询）和辨析文本
+    let en_options = TextOptions::default().set_indexing_options(
+        TextFieldIndexing::default()
+            .set_tokenizer("en_stem")
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+    );
+    let _explanation_en = schema_builder.add_text_field("explanation_en", en_options.clone());
+    let _example_en = schema_builder.add_text_field("example_en", en_options.clone());
+    let _analysis = schema_builder.add_text_field("analysis", en_options);
+
+    // 源文件相对 words 目录的路径（增量同步时按此删除旧文档）
+    let _path = schema_builder.add_text_field("path", STRING | STORED);
+
     schema_builder.build()
 }
 
+// 一次索引同步的统计结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+    pub errors: usize,
+    pub total_files: usize,
+    // 是否进行了全量重建（索引不存在、schema 变化或清单缺失时）
+    pub rebuilt: bool,
+    // 新增、修改或删除的词条
+    pub changed_words: Vec<String>,
+    // 每个目录（相对 words 目录，顶层为 "."）中的词条文件数；只在遍历整个 words 目录时统计
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub directories: BTreeMap<String, usize>,
+    // 未能建立索引的文件（词典包为 包文件:行号）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped_files: Vec<String>,
+    // 解析错误、读取失败和重复词头等问题
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub issues: Vec<BuildIssue>,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BuildIssueKind {
+    // 无法读取文件
+    Unreadable,
+    // JSON 语法错误或不符合 WordDefinition 的结构
+    ParseError,
+    // tantivy 拒绝了文档
+    IndexError,
+    // 同一词头出现在多个文件中，查词时只会找到其中一个
+    DuplicateHeadword,
+}
+
+impl BuildIssueKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            BuildIssueKind::Unreadable => "unreadable",
+            BuildIssueKind::ParseError => "parse error",
+            BuildIssueKind::IndexError => "index error",
+            BuildIssueKind::DuplicateHeadword => "duplicate headword",
+        }
+    }
+}
+
+// 建立索引时发现的一个问题；file 为文件的完整路径（词典包为包文件的路径）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildIssue {
+    pub kind: BuildIssueKind,
+    pub file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl BuildIssue {
+    // 文件:行:列
+    pub fn location(&self) -> String {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => format!("{}:{}:{}", self.file, line, column),
+            (Some(line), None) => format!("{}:{}", self.file, line),
+            _ => self.file.clone(),
+        }
+    }
+}
+
+impl SyncSummary {
+    // 累加另一个词典的同步结果
+    pub fn merge(&mut self, other: SyncSummary) {
+        self.added += other.added;
+        self.updated += other.updated;
+        self.removed += other.removed;
+        self.unchanged += other.unchanged;
+        self.errors += other.errors;
+        self.total_files += other.total_files;
+        self.rebuilt |= other.rebuilt;
+        self.changed_words.extend(other.changed_words);
+        for (directory, count) in other.directories {
+            *self.directories.entry(directory).or_insert(0) += count;
+        }
+        self.skipped_files.extend(other.skipped_files);
+        self.issues.extend(other.issues);
+        self.elapsed_ms += other.elapsed_ms;
+    }
+
+    // 跳过一个无法建立索引的词条：计入 errors，并记录文件和原因
+    fn skip(&mut self, kind: BuildIssueKind, origin: &EntryOrigin, position: Option<(usize, usize)>, message: String) {
+        self.skip_issue(origin.label(), origin.issue(kind, position, message));
+    }
+
+    // 同 skip，问题已经生成（或来自清单中记录的上次失败）
+    fn skip_issue(&mut self, skipped: String, issue: BuildIssue) {
+        self.errors += 1;
+        self.skipped_files.push(skipped);
+        self.issues.push(issue);
+    }
+}
+
+// 构建进度：已处理的文件数和文件总数（词典包的总行数事先不知道，为 0）
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct BuildProgress {
+    pub processed: usize,
+    pub total: usize,
+}
+
+pub type ProgressCallback = Arc<dyn Fn(BuildProgress) + Send + Sync>;
+
+// 构建过程的控制：进度回调（在构建线程中调用）和取消令牌。
+// 取消时全量重建丢弃临时目录，增量同步回滚未提交的修改，现有索引保持不变
+#[derive(Clone, Default)]
+pub struct BuildControl {
+    pub progress: Option<ProgressCallback>,
+    pub cancel: CancellationToken,
+}
+
+impl BuildControl {
+    fn report(&self, processed: usize, total: usize) {
+        if let Some(progress) = &self.progress {
+            progress(BuildProgress { processed, total });
+        }
+    }
+
+    fn check_cancelled(&self) -> Result<(), String> {
+        if self.cancel.is_cancelled() {
+            Err(BUILD_CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// 构建线程中所有的取消检查都通过 check_cancelled 返回这个错误，run_sync 据此换成 BuildCancelled
+const BUILD_CANCELLED: &str = "Index build cancelled";
+
+// 构建被取消时返回的错误，可以用 downcast_ref 识别
+#[derive(Debug)]
+pub struct BuildCancelled;
+
+impl std::fmt::Display for BuildCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(BUILD_CANCELLED)
+    }
+}
+
+impl std::error::Error for BuildCancelled {}
+
+// IndexWriter 内存预算的默认值和下限（tantivy 要求每个索引线程至少 15 MB）
+pub const DEFAULT_WRITER_MEMORY: usize = 50_000_000;
+pub const MIN_WRITER_MEMORY: usize = 15_000_000;
+
+// 每批并行读取、解析的文件（或词典包的行）数；每批之间报告进度、检查是否取消
+const BUILD_BATCH_SIZE: usize = 256;
+
+// 构建索引使用的资源：IndexWriter 的内存预算（tantivy 按预算决定索引线程数，
+// 每个线程 15 MB，最多 8 个），以及读取和解析词条的线程数（0 为 CPU 核数）
+#[derive(Debug, Clone, Copy)]
+pub struct BuildSettings {
+    pub writer_memory: usize,
+    pub threads: usize,
+}
+
+impl Default for BuildSettings {
+    fn default() -> Self {
+        BuildSettings {
+            writer_memory: DEFAULT_WRITER_MEMORY,
+            threads: 0,
+        }
+    }
+}
+
+impl BuildSettings {
+    fn thread_pool(&self) -> Result<rayon::ThreadPool, String> {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(self.threads)
+            .thread_name(|i| format!("dictnavi-index-{}", i))
+            .build()
+            .map_err(|e| format!("Failed to start indexing threads: {}", e))
+    }
+}
+
+// 全文检索命中的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchedField {
+    Explanation,
+    Example,
+    Comparison,
+}
+
+impl MatchedField {
+    const ALL: [MatchedField; 3] = [MatchedField::Explanation, MatchedField::Example, MatchedField::Comparison];
+
+    fn field_name(self) -> &'static str {
+        match self {
+            MatchedField::Explanation => "explanation_en",
+            MatchedField::Example => "example_en",
+            MatchedField::Comparison => "analysis",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MatchedField::Explanation => "explanation",
+            MatchedField::Example => "example",
+            MatchedField::Comparison => "comparison",
+        }
+    }
+
+    // 词条中该字段的全部文本（多个义项或辨析以空格连接）
+    fn text_of(self, definition: &WordDefinition) -> String {
+        let values: Vec<&str> = match self {
+            MatchedField::Explanation => definition.meanings.iter().flatten().map(|m| m.explanation_en.as_str()).collect(),
+            MatchedField::Example => definition.meanings.iter().flatten().filter_map(|m| m.example_en.as_deref()).collect(),
+            MatchedField::Comparison => definition.comparisons.iter().flatten().filter_map(|c| c.analysis.as_deref()).collect(),
+        };
+        values.join(" ")
+    }
+}
+
+// 全文检索结果：snippet 为命中片段，highlights 为片段中需要高亮的字节区间
+#[derive(Debug, Clone, Serialize)]
+pub struct FullTextHit {
+    // 命中的词典（DictionarySet 中的来源名）
+    pub source: String,
+    pub word: String,
+    pub field: MatchedField,
+    pub snippet: String,
+    pub highlights: Vec<std::ops::Range<usize>>,
+    pub score: f32,
+}
+
+// 索引概况（命令行 stats 使用）
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexStats {
+    pub name: String,
+    pub words_directory: String,
+    pub index_path: String,
+    // 索引中的词条数
+    pub entries: u64,
+    // 清单中记录的源文件数
+    pub indexed_files: usize,
+    pub segments: usize,
+    // 索引目录占用的磁盘空间（字节）
+    pub index_size_bytes: u64,
+}
+
+// 查找结果：词条、所在的词典，以及通过屈折形式命中时的形式信息
+#[derive(Debug, Clone)]
+pub struct LookupResult {
+    pub source: String,
+    pub definition: WordDefinition,
+    pub inflection: Option<Inflection>,
+}
+
+// 打开索引并注册自定义分词器（分词器不会持久化到索引中，每次打开都需要注册）
+fn open_index(path: &str) -> tantivy::Result<Index> {
+    let index = Index::open_in_dir(path)?;
+    register_tokenizers(&index);
+    Ok(index)
+}
+
+fn create_index(path: &Path, schema: Schema) -> tantivy::Result<Index> {
+    let index = Index::create_in_dir(path, schema)?;
+    register_tokenizers(&index);
+    Ok(index)
+}
+
+fn register_tokenizers(index: &Index) {
+    index.tokenizers().register(CJK_TOKENIZER, CjkTokenizer);
+}
+
+// 词典未命名时的名称
+pub const DEFAULT_DICTIONARY_NAME: &str = "default";
+
 pub struct Dictionary {
+    // 词典名称，多个词典同时加载时用于区分结果来源
+    name: String,
     words_directory: String,
     index_path: String,
     schema: Schema,
     index: Arc<RwLock<Option<Index>>>,
+    // 自动完成 FST（按需加载，索引变化后清空）
+    autocomplete: RwLock<Option<Arc<Autocomplete>>>,
+    // 串行化所有写索引的操作（同步、重建、文件监视器触发的重新索引）
+    write_lock: Arc<Mutex<()>>,
+    build_settings: BuildSettings,
 }
 
 impl Dictionary {
+    // 索引放在 words 目录下的 .index 中；来源为词典包时放在包文件旁的 <包文件名>.index 中
     pub fn new(words_directory: String) -> Self {
-        let index_path = format!("{}/.index", words_directory);
+        let index_path = if pack::is_pack(Path::new(&words_directory)) {
+            format!("{}.index", words_directory)
+        } else {
+            format!("{}/.index", words_directory)
+        };
+        Self::with_index_path(words_directory, index_path)
+    }
+
+    // 索引放在单独的目录中（words 目录只读时使用可写的缓存目录）
+    pub fn with_index_path(words_directory: String, index_path: String) -> Self {
         let schema = build_schema();
-        
+
         Dictionary {
+            name: DEFAULT_DICTIONARY_NAME.to_string(),
             words_directory,
             index_path,
             schema,
             index: Arc::new(RwLock::new(None)),
+            autocomplete: RwLock::new(None),
+            write_lock: Arc::new(Mutex::new(())),
+            build_settings: BuildSettings::default(),
         }
     }
 
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn with_build_settings(mut self, settings: BuildSettings) -> Self {
+        self.build_settings = settings;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn words_directory(&self) -> &str {
+        &self.words_directory
+    }
+
+    pub fn index_path(&self) -> &str {
+        &self.index_path
+    }
+
     // 检查索引是否存在且有效（不自动构建）
     fn check_index_exists(&self) -> Result<bool, Box<dyn std::error::Error>> {
         let index_dir = Path::new(&self.index_path);
@@ -76,58 +440,33 @@ impl Dictionary {
         }
     }
 
-    // 检查是否需要重建索引（通过比较文件数量和修改时间）
+    // 检查索引是否与 words 目录一致：每个词条文件都在清单中（已索引或记录为失败），
+    // 且修改时间和大小与清单一致；清单中也没有已删除的文件。不读取文件内容
     // 注意：此方法假设索引目录已存在
     fn needs_rebuild(&self) -> Result<bool, Box<dyn std::error::Error>> {
         let words_dir = Path::new(&self.words_directory);
         if !words_dir.exists() {
             return Ok(true);
         }
-
-        // 统计 words 目录中的 JSON 文件数量
-        let mut json_count = 0;
-        let mut latest_mtime = SystemTime::UNIX_EPOCH;
-        
-        for entry in fs::read_dir(&self.words_directory)? {
-            let entry = entry?;
-            let path = entry.path();
-            
-            if path.extension().map(|s| s == "json").unwrap_or(false) {
-                json_count += 1;
-                if let Ok(metadata) = fs::metadata(&path) {
-                    if let Ok(mtime) = metadata.modified() {
-                        if mtime > latest_mtime {
-                            latest_mtime = mtime;
-                        }
-                    }
-                }
-            }
+        if pack::is_pack(words_dir) {
+            return Ok(self.pack_needs_rebuild());
         }
 
-        // 检查索引中的文档数量是否与文件数量匹配
-        match Index::open_in_dir(&self.index_path) {
-            Ok(index) => {
-                let reader = index.reader()?;
-                let searcher = reader.searcher();
-                let indexed_count = searcher.num_docs() as usize;
-                
-                // 如果文档数量不匹配，需要重建
-                if indexed_count != json_count {
-                    return Ok(true);
-                }
+        // 索引结构与当前版本不同（例如新增了字段）或无法打开时需要重建
+        let schema_matches = Index::open_in_dir(&self.index_path).is_ok_and(|index| index.schema() == self.schema);
+        let Some(manifest) = IndexManifest::load(&self.index_path).filter(|_| schema_matches) else {
+            return Ok(true);
+        };
 
-                // 检查索引目录的修改时间
-                if let Ok(index_metadata) = fs::metadata(&self.index_path) {
-                    if let Ok(index_mtime) = index_metadata.modified() {
-                        // 如果 words 目录中有文件比索引更新，需要重建
-                        if latest_mtime > index_mtime {
-                            return Ok(true);
-                        }
-                    }
-                }
-            }
-            Err(_) => {
-                // 如果无法打开索引，需要重建
+        let files = scan::word_files(words_dir)?;
+        if files.len() != manifest.entries.len() + manifest.failed.len() {
+            return Ok(true);
+        }
+        for file in files {
+            let current = fs::metadata(&file.path)
+                .ok()
+                .map(|metadata| (manifest::mtime_ns(&metadata), metadata.len()));
+            if current.is_none() || manifest.recorded_state(&file.rel_path) != current {
                 return Ok(true);
             }
         }
@@ -135,115 +474,117 @@ impl Dictionary {
         Ok(false)
     }
 
-    // 异步构建索引：扫描 words 目录下的所有 JSON 文件并建立 tantivy 索引
-    pub async fn build_index_async(&self) -> Result<(usize, usize), Box<dyn std::error::Error>> {
+    // 词典包：只比较包文件的修改时间和大小与清单中记录的是否一致，不读取包的内容
+    fn pack_needs_rebuild(&self) -> bool {
+        let Ok(metadata) = fs::metadata(&self.words_directory) else {
+            return true;
+        };
+        let current = PackState {
+            mtime_ns: manifest::mtime_ns(&metadata),
+            size: metadata.len(),
+        };
+
+        let schema_matches = Index::open_in_dir(&self.index_path).is_ok_and(|index| index.schema() == self.schema);
+        let synced = IndexManifest::load(&self.index_path).is_some_and(|manifest| manifest.pack == Some(current));
+        !(schema_matches && synced)
+    }
+
+    // 索引是否存在且有文档；不检查是否与 words 目录一致，不访问词条文件
+    pub fn index_exists(&self) -> Result<bool, Box<dyn std::error::Error>> {
+        recover_interrupted_swap(&self.index_path);
+        self.check_index_exists()
+    }
+
+    // 启动时检查：索引不存在、无效或与 words 目录不一致时返回 true
+    pub fn needs_index_build(&self) -> Result<bool, Box<dyn std::error::Error>> {
+        recover_interrupted_swap(&self.index_path);
+
+        if !self.check_index_exists()? {
+            return Ok(true);
+        }
+        self.needs_rebuild()
+    }
+
+    // 异步全量重建索引：在临时目录中重新索引 words 目录下的所有 JSON 文件，成功后替换现有索引。
+    // 返回构建报告：统计、跳过的文件及原因（带行列号）、重复词头和耗时。
+    // control 报告进度并可以取消构建，取消时返回 BuildCancelled，现有索引不变
+    pub async fn build_index_async(&self, control: BuildControl) -> Result<SyncSummary, Box<dyn std::error::Error>> {
+        self.run_sync(true, control).await
+    }
+
+    // 同 build_index_async，不报告进度
+    pub async fn rebuild_index_async(&self) -> Result<SyncSummary, Box<dyn std::error::Error>> {
+        self.run_sync(true, BuildControl::default()).await
+    }
+
+    // 异步增量同步索引：只处理新增、修改和删除的 JSON 文件
+    pub async fn sync_index_async(&self) -> Result<SyncSummary, Box<dyn std::error::Error>> {
+        self.run_sync(false, BuildControl::default()).await
+    }
+
+    // 同 sync_index_async，通过 control 报告进度并可以取消
+    pub async fn sync_index_with_control(&self, control: BuildControl) -> Result<SyncSummary, Box<dyn std::error::Error>> {
+        self.run_sync(false, control).await
+    }
+
+    async fn run_sync(&self, force_rebuild: bool, control: BuildControl) -> Result<SyncSummary, Box<dyn std::error::Error>> {
         // 使用 tokio::task::spawn_blocking 将阻塞的 I/O 操作移到线程池
         let words_dir = self.words_directory.clone();
         let index_path = self.index_path.clone();
         let schema = self.schema.clone();
-        
-        let result = tokio::task::spawn_blocking(move || {
-            // 确保 words 目录存在
-            let words_dir_path = Path::new(&words_dir);
-            if !words_dir_path.exists() {
-                fs::create_dir_all(&words_dir).map_err(|e| format!("Failed to create words directory: {}", e))?;
-                println!("Created words directory: {}", words_dir);
-            }
+        let write_lock = Arc::clone(&self.write_lock);
+        let cache = Arc::clone(&self.index);
+        let settings = self.build_settings;
 
-            // 如果索引目录已存在，先删除
-            if Path::new(&index_path).exists() {
-                fs::remove_dir_all(&index_path).map_err(|e| format!("Failed to remove index directory: {}", e))?;
-                println!("Removed existing index directory");
-            }
+        let result = tokio::task::spawn_blocking(move || {
+            let _guard = write_lock.lock().unwrap_or_else(|e| e.into_inner());
+            sync_index_blocking(&words_dir, &index_path, &schema, force_rebuild, &settings, &control, &cache)
+        })
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?;
+        let summary = match result {
+            Ok(summary) => summary,
+            // 只有取消检查本身返回的错误才是取消；取消前已经发生的其他错误照常报告
+            Err(e) if e == BUILD_CANCELLED => return Err(Box::new(BuildCancelled)),
+            Err(e) => return Err(e.into()),
+        };
 
-            // 确保索引目录的父目录存在
-            if let Some(parent) = Path::new(&index_path).parent() {
-                fs::create_dir_all(parent).map_err(|e| format!("Failed to create parent directory: {}", e))?;
-            }
+        // 重建时 rebuild_into_staging 已经在替换目录后载入了新索引；增量同步则先打开索引再替换缓存，
+        // 替换前的查询一直使用旧索引
+        if !summary.rebuilt {
+            let index = open_index(&self.index_path)
+                .map_err(|e| format!("无法打开索引 {}: {}。请重新构建索引。", self.index_path, e))?;
+            *self.index.write().unwrap() = Some(index);
+        }
+        *self.autocomplete.write().unwrap() = None;
 
-            // 创建索引目录本身（Index::create_in_dir 需要目录已存在）
-            fs::create_dir_all(&index_path).map_err(|e| format!("Failed to create index directory: {}", e))?;
+        Ok(summary)
+    }
 
-            println!("Building index from words directory: {}", words_dir);
-            let index = Index::create_in_dir(&index_path, schema.clone())
-                .map_err(|e| format!("Failed to create index: {}", e))?;
-            let mut index_writer = index.writer(50_000_000)
-                .map_err(|e| format!("Failed to create index writer: {}", e))?; // 50MB buffer
+    // 已消失的路径是否是索引过的目录（文件监视器用来区分被删除的目录和编辑器的临时文件）
+    pub fn is_indexed_directory(&self, path: &Path) -> bool {
+        let words_dir = Path::new(&self.words_directory)
+            .canonicalize()
+            .unwrap_or_else(|_| PathBuf::from(&self.words_directory));
+        IndexManifest::load(&self.index_path).is_some_and(|manifest| is_indexed_directory(&manifest, &words_dir, path))
+    }
 
-            let word_field = schema.get_field("word")
-                .map_err(|e| format!("Failed to get word field: {}", e))?;
-            let concise_definition_field = schema.get_field("concise_definition")
-                .map_err(|e| format!("Failed to get concise_definition field: {}", e))?;
-            let json_data_field = schema.get_field("json_data")
-                .map_err(|e| format!("Failed to get json_data field: {}", e))?;
+    // 重新索引指定的词条文件（阻塞调用，供文件监视器使用）
+    pub fn reindex_files(&self, paths: &[PathBuf]) -> Result<SyncSummary, Box<dyn std::error::Error>> {
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let summary = reindex_files_blocking(
+            &self.words_directory,
+            &self.index_path,
+            &self.schema,
+            &self.build_settings,
+            paths,
+            &self.index,
+        )?;
 
-            let mut indexed_count = 0;
-            let mut error_count = 0;
-            
-            // 统计words目录中的JSON文件数量
-            let mut json_count = 0;
-            
-            // 遍历 words 目录下的所有 JSON 文件
-            for entry in fs::read_dir(&words_dir).map_err(|e| format!("Failed to read words directory: {}", e))? {
-                let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
-                let path = entry.path();
-                
-                if path.extension().map(|s| s == "json").unwrap_or(false) {
-                    json_count += 1;
-                    match fs::read_to_string(&path) {
-                        Ok(data) => {
-                            // 解析 JSON 以获取单词和简明释义
-                            match serde_json::from_str::<WordDefinition>(&data) {
-                                Ok(word_def) => {
-                                    let concise = word_def
-                                        .concise_definition
-                                        .clone()
-                                        .unwrap_or_default();
-                                    
-                                    if let Err(e) = index_writer.add_document(tantivy::doc!(
-                                        word_field => word_def.word.clone(),
-                                        concise_definition_field => concise,
-                                        json_data_field => data
-                                    )) {
-                                        eprintln!("Warning: Failed to add document for {:?}: {}", path, e);
-                                        error_count += 1;
-                                    } else {
-                                        indexed_count += 1;
-                                    }
-                                }
-                                Err(e) => {
-                                    eprintln!("Warning: Failed to parse JSON file {:?}: {}", path, e);
-                                    error_count += 1;
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("Warning: Failed to read file {:?}: {}", path, e);
-                            error_count += 1;
-                        }
-                    }
-                }
-            }
+        // 重建后的索引已由 rebuild_into_staging 载入缓存，这里只需清空自动完成
+        *self.autocomplete.write().unwrap() = None;
 
-            index_writer.commit().map_err(|e| format!("Failed to commit index: {}", e))?;
-            println!("Index built successfully with {} documents ({} errors)", indexed_count, error_count);
-            
-            Ok::<(usize, usize, usize), String>((indexed_count, error_count, json_count))
-        })
-        .await
-        .map_err(|e| format!("Task join error: {}", e))?;
-        
-        let (indexed_count, _error_count, json_count) = result?;
-        
-        // 清空缓存的索引，强制重新加载
-        let mut index_guard = self.index.write().unwrap();
-        *index_guard = None;
-        drop(index_guard);
-        
-        // 重新加载索引
-        self.ensure_index_loaded()?;
-        
-        Ok((indexed_count, json_count))
+        Ok(summary)
     }
 
    // 确保 index 已加载（不自动构建）
@@ -251,6 +592,8 @@ impl Dictionary {
         let mut index_guard = self.index.write().unwrap();
         
         if index_guard.is_none() {
+            recover_interrupted_swap(&self.index_path);
+
             // 检查索引目录是否存在
             let index_dir = Path::new(&self.index_path);
             if !index_dir.exists() {
@@ -259,7 +602,7 @@ impl Dictionary {
             }
             
             // 尝试打开索引，如果失败则返回错误
-            match Index::open_in_dir(&self.index_path) {
+            match open_index(&self.index_path) {
                 Ok(index) => {
                     *index_guard = Some(index);
                 }
@@ -283,66 +626,170 @@ impl Dictionary {
             .map(|idx| Arc::new(idx.clone()))
     }
 
-    // 查找单词（精确匹配）
+    // 查找单词：先精确匹配词头，找不到时按屈折形式查找对应的原形
     pub fn lookup_word(&self, word: &str) -> Result<Option<WordDefinition>, Box<dyn std::error::Error>> {
+        Ok(self.lookup(word)?.map(|result| result.definition))
+    }
+
+    // 查找单词，并在通过屈折形式命中时返回形式信息（例如 abandoned → past tense of abandon）
+    pub fn lookup(&self, word: &str) -> Result<Option<LookupResult>, Box<dyn std::error::Error>> {
         let index = self.get_index()?;
         let reader = index.reader()?;
         let searcher = reader.searcher();
         let schema = searcher.schema();
 
-        let word_field = schema.get_field("word")?;
-        let json_data_field = schema.get_field("json_data")?;
+        let headword_field = schema.get_field("headword")?;
+        let forms_field = schema.get_field("forms")?;
+        let query_word = normalize_headword(word);
+
+        if let Some(definition) = self.find_by_term(&searcher, headword_field, &query_word)? {
+            return Ok(Some(LookupResult {
+                source: self.name.clone(),
+                definition,
+                inflection: None,
+            }));
+        }
+
+        if let Some(definition) = self.find_by_term(&searcher, forms_field, &query_word)? {
+            let inflection = definition.inflection_of(&query_word);
+            return Ok(Some(LookupResult {
+                source: self.name.clone(),
+                definition,
+                inflection,
+            }));
+        }
+
+        Ok(None)
+    }
+
+    // 按字段中的单个词项精确查找，返回第一个匹配的词条
+    fn find_by_term(
+        &self,
+        searcher: &tantivy::Searcher,
+        field: Field,
+        text: &str,
+    ) -> Result<Option<WordDefinition>, Box<dyn std::error::Error>> {
+        let json_data_field = searcher.schema().get_field("json_data")?;
 
         let query = TermQuery::new(
-            Term::from_field_text(word_field, &word.to_lowercase()),
+            Term::from_field_text(field, text),
             tantivy::schema::IndexRecordOption::Basic,
         );
 
         let top_docs = searcher.search(&query, &TopDocs::with_limit(1))?;
-        
+
         if let Some((_score, doc_address)) = top_docs.first() {
             let retrieved_doc: tantivy::TantivyDocument = searcher.doc(*doc_address)?;
-            if let Some(json_val) = retrieved_doc.get_first(json_data_field) {
-                if let Some(json_str) = json_val.as_str() {
-                    return Ok(Some(serde_json::from_str(json_str)?));
-                }
+            if let Some(json_str) = retrieved_doc.get_first(json_data_field).and_then(|v| v.as_str()) {
+                return Ok(Some(serde_json::from_str(json_str)?));
             }
         }
-        
+
         Ok(None)
     }
 
-    // 列出所有单词（用于自动完成）
+    // 列出所有单词（来自自动完成 FST，不受词典大小限制）
     pub fn list_words(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        Ok(self.get_autocomplete()?.all_words())
+    }
+
+    // 索引概况：词条数、段数量和磁盘占用
+    pub fn stats(&self) -> Result<IndexStats, Box<dyn std::error::Error>> {
+        let index = self.get_index()?;
+        let reader = index.reader()?;
+        let searcher = reader.searcher();
+
+        let mut index_size_bytes = 0;
+        for entry in fs::read_dir(&self.index_path)? {
+            let metadata = entry?.metadata()?;
+            if metadata.is_file() {
+                index_size_bytes += metadata.len();
+            }
+        }
+
+        Ok(IndexStats {
+            name: self.name.clone(),
+            words_directory: self.words_directory.clone(),
+            index_path: self.index_path.clone(),
+            entries: searcher.num_docs(),
+            indexed_files: IndexManifest::load(&self.index_path).map(|m| m.entries.len()).unwrap_or(0),
+            segments: searcher.segment_readers().len(),
+            index_size_bytes,
+        })
+    }
+
+    // 自动完成：以 prefix 开头的词头，按字母顺序返回至多 limit 个
+    pub fn complete_prefix(&self, prefix: &str, limit: usize) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        Ok(self.get_autocomplete()?.complete(prefix, limit))
+    }
+
+    // 获取自动完成 FST（内部使用）；文件缺失时（旧版本构建的索引）根据清单补建
+    fn get_autocomplete(&self) -> Result<Arc<Autocomplete>, Box<dyn std::error::Error>> {
+        if let Some(autocomplete) = self.autocomplete.read().unwrap().as_ref() {
+            return Ok(Arc::clone(autocomplete));
+        }
+
+        self.ensure_index_loaded()?;
+
+        let autocomplete = match Autocomplete::load(&self.index_path) {
+            Some(autocomplete) => autocomplete,
+            None => {
+                let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+                let manifest = IndexManifest::load(&self.index_path)
+                    .ok_or_else(|| format!("自动完成索引不可用，请重新构建索引: {}", self.index_path))?;
+                Autocomplete::build(manifest.entries.values().map(|entry| entry.word.as_str()), &self.index_path)?;
+                Autocomplete::load(&self.index_path)
+                    .ok_or_else(|| format!("自动完成索引不可用，请重新构建索引: {}", self.index_path))?
+            }
+        };
+
+        let autocomplete = Arc::new(autocomplete);
+        *self.autocomplete.write().unwrap() = Some(Arc::clone(&autocomplete));
+        Ok(autocomplete)
+    }
+
+    // 前缀搜索：自动完成 FST 给出按字母顺序的词头，再逐个取简明释义
+    pub fn search_words(&self, prefix: &str, limit: usize) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+        let words = self.complete_prefix(prefix, limit)?;
+
         let index = self.get_index()?;
         let reader = index.reader()?;
         let searcher = reader.searcher();
         let schema = searcher.schema();
-        let word_field = schema.get_field("word")?;
 
-        // 使用 QueryParser 进行前缀查询（使用通配符 "*" 匹配所有）
-        let query_parser = QueryParser::for_index(&index, vec![word_field]);
-        let query = query_parser.parse_query("*")?;
-        
-        // 获取所有文档（设置一个很大的限制）
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(100_000))?;
+        let headword_field = schema.get_field("headword")?;
+        let concise_field = schema.get_field("concise_definition")?;
 
-        let mut words = Vec::new();
-        for (_score, doc_address) in top_docs {
-            let retrieved_doc: tantivy::TantivyDocument = searcher.doc(doc_address)?;
-            if let Some(word_val) = retrieved_doc.get_first(word_field) {
-                if let Some(word_str) = word_val.as_str() {
-                    words.push(word_str.to_string());
+        let mut results = Vec::new();
+        for word in words {
+            let query = TermQuery::new(
+                Term::from_field_text(headword_field, &normalize_headword(&word)),
+                IndexRecordOption::Basic,
+            );
+            let concise = match searcher.search(&query, &TopDocs::with_limit(1))?.first() {
+                Some((_score, doc_address)) => {
+                    let retrieved_doc: tantivy::TantivyDocument = searcher.doc(*doc_address)?;
+                    retrieved_doc
+                        .get_first(concise_field)
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("")
+                        .to_string()
                 }
-            }
+                None => String::new(),
+            };
+            results.push((word, concise));
         }
-        
-        words.sort();
-        Ok(words)
+
+        Ok(results)
     }
 
-    // 前缀搜索（模糊查询，用于更高效的自动完成）
-    pub fn search_words(&self, prefix: &str) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+    // 汉译英反查：按中文释义匹配英文词头，简明释义中的匹配权重更高，结果按相关度排序
+    pub fn reverse_search(&self, query: &str, limit: usize) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+        let terms = cjk::query_terms(query);
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
         let index = self.get_index()?;
         let reader = index.reader()?;
         let searcher = reader.searcher();
@@ -350,13 +797,27 @@ impl Dictionary {
 
         let word_field = schema.get_field("word")?;
         let concise_field = schema.get_field("concise_definition")?;
+        let concise_cn_field = schema.get_field("concise_cn")?;
+        let explanation_cn_field = schema.get_field("explanation_cn")?;
 
-        // 使用 QueryParser 进行前缀查询
-        let query_parser = QueryParser::for_index(&index, vec![word_field]);
-        let query_str = format!("{}*", prefix.to_lowercase());
-        let query = query_parser.parse_query(&query_str)?;
-        
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(20))?;
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        for term in &terms {
+            let concise_query = TermQuery::new(
+                Term::from_field_text(concise_cn_field, term),
+                IndexRecordOption::WithFreqs,
+            );
+            clauses.push((Occur::Should, Box::new(BoostQuery::new(Box::new(concise_query), 3.0))));
+            clauses.push((
+                Occur::Should,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(explanation_cn_field, term),
+                    IndexRecordOption::WithFreqs,
+                )),
+            ));
+        }
+        let query = BooleanQuery::new(clauses);
+
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
 
         let mut results = Vec::new();
         for (_score, doc_address) in top_docs {
@@ -373,7 +834,1383 @@ impl Dictionary {
                 .to_string();
             results.push((word, concise));
         }
-        
+
         Ok(results)
     }
+
+    // 全文检索：在英文释义、例句和辨析中查找（默认要求所有词都出现，支持 "abandon ship" 短语查询）
+    pub fn full_text_search(&self, query: &str, limit: usize) -> Result<Vec<FullTextHit>, Box<dyn std::error::Error>> {
+        if query.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let index = self.get_index()?;
+        let reader = index.reader()?;
+        let searcher = reader.searcher();
+        let schema = searcher.schema();
+        let json_data_field = schema.get_field("json_data")?;
+
+        let mut fields = Vec::new();
+        for matched_field in MatchedField::ALL {
+            fields.push((matched_field, schema.get_field(matched_field.field_name())?));
+        }
+
+        let mut query_parser = QueryParser::for_index(&index, fields.iter().map(|(_, field)| *field).collect());
+        query_parser.set_conjunction_by_default();
+        let (parsed_query, _errors) = query_parser.parse_query_lenient(query);
+
+        // 每个字段单独解析同一查询：命中的文档中，单独满足查询的字段才是命中字段
+        let mut field_queries = Vec::new();
+        for (matched_field, field) in &fields {
+            let mut field_parser = QueryParser::for_index(&index, vec![*field]);
+            field_parser.set_conjunction_by_default();
+            let (field_query, _errors) = field_parser.parse_query_lenient(query);
+            let generator = SnippetGenerator::create(&searcher, &*field_query, *field)?;
+            let weight = field_query.weight(EnableScoring::enabled_from_searcher(&searcher))?;
+            field_queries.push((*matched_field, weight, generator));
+        }
+
+        let top_docs = searcher.search(&*parsed_query, &TopDocs::with_limit(limit))?;
+
+        let mut hits = Vec::new();
+        for (score, doc_address) in top_docs {
+            let retrieved_doc: tantivy::TantivyDocument = searcher.doc(doc_address)?;
+            let Some(json_str) = retrieved_doc.get_first(json_data_field).and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let definition: WordDefinition = serde_json::from_str(json_str)?;
+
+            // 取单独满足查询且得分最高的字段；查询的词分散在多个字段中时，取高亮最多的字段
+            let mut best: Option<(Option<f32>, Snippet, MatchedField)> = None;
+            for (matched_field, weight, generator) in &field_queries {
+                let field_score = score_doc(&searcher, &**weight, doc_address)?;
+                let snippet = generator.snippet(&matched_field.text_of(&definition));
+                let better = match &best {
+                    None => true,
+                    Some((best_score, best_snippet, _)) => match (field_score, best_score) {
+                        (Some(a), Some(b)) => a > *b,
+                        (Some(_), None) => true,
+                        (None, Some(_)) => false,
+                        (None, None) => snippet.highlighted().len() > best_snippet.highlighted().len(),
+                    },
+                };
+                if better {
+                    best = Some((field_score, snippet, *matched_field));
+                }
+            }
+
+            let hit = best
+                .filter(|(field_score, snippet, _)| field_score.is_some() || !snippet.highlighted().is_empty())
+                .map(|(_, snippet, field)| FullTextHit {
+                    source: self.name.clone(),
+                    word: definition.word.clone(),
+                    field,
+                    snippet: snippet.fragment().to_string(),
+                    highlights: snippet.highlighted().to_vec(),
+                    score,
+                });
+
+            hits.push(hit.unwrap_or_else(|| FullTextHit {
+                source: self.name.clone(),
+                word: definition.word.clone(),
+                field: MatchedField::Explanation,
+                snippet: String::new(),
+                highlights: Vec::new(),
+                score,
+            }));
+        }
+
+        Ok(hits)
+    }
+
+    // 拼写纠错：返回与 word 编辑距离最近的词头（"did you mean"），按编辑距离排序。
+    // 词库没有词频数据，距离相同时按义项数排序（义项多的词优先），再按字母顺序。
+    // 候选词头直接从各段的词头词典中用 Levenshtein 自动机列出，先排序再截断到 limit
+    pub fn suggest_similar(&self, word: &str, limit: usize) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        // 短词只允许一处编辑，否则候选太多
+        let max_distance = if normalize_headword(word).chars().count() <= 3 { 1 } else { 2 };
+        self.similar_headwords(word, max_distance, limit)
+    }
+
+    // 与 word 的编辑距离不超过 max_distance 的词头（不含 word 本身），排序同 suggest_similar
+    pub fn similar_headwords(&self, word: &str, max_distance: u8, limit: usize) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let query_word = normalize_headword(word);
+        if query_word.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let index = self.get_index()?;
+        let reader = index.reader()?;
+        let searcher = reader.searcher();
+        let headword_field = searcher.schema().get_field("headword")?;
+
+        let dfa = LevenshteinAutomatonBuilder::new(max_distance, true).build_dfa(&query_word);
+
+        let mut by_distance: BTreeMap<usize, BTreeSet<String>> = BTreeMap::new();
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(headword_field)?;
+            let mut terms = inverted_index.terms().search(LevenshteinDfa(&dfa)).into_stream()?;
+            while terms.advance() {
+                let Ok(candidate) = std::str::from_utf8(terms.key()) else {
+                    continue;
+                };
+                if candidate != query_word {
+                    let distance = edit_distance(&query_word, candidate);
+                    by_distance.entry(distance).or_default().insert(candidate.to_string());
+                }
+            }
+        }
+
+        // 按距离从近到远读取词条，同一距离的候选全部读取后再排序，
+        // 已删除文档留下的词项读不到词条，跳过
+        let mut suggestions: Vec<String> = Vec::new();
+        for candidates in by_distance.into_values() {
+            let mut ranked: Vec<(usize, String)> = Vec::new();
+            for candidate in candidates {
+                if let Some(definition) = self.find_by_term(&searcher, headword_field, &candidate)? {
+                    let sense_count = definition.meanings.as_ref().map_or(0, Vec::len);
+                    ranked.push((sense_count, definition.word));
+                }
+            }
+            ranked.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+            suggestions.extend(ranked.into_iter().map(|(_, word)| word));
+            if suggestions.len() >= limit {
+                break;
+            }
+        }
+
+        suggestions.truncate(limit);
+        Ok(suggestions)
+    }
+}
+
+// 文档满足查询时返回它的得分。不用 Query::explain：新建的 scorer 可能已经越过目标文档，
+// 此时 seek 会触发断言
+fn score_doc(searcher: &tantivy::Searcher, weight: &dyn Weight, doc_address: DocAddress) -> tantivy::Result<Option<f32>> {
+    let segment_reader = searcher.segment_reader(doc_address.segment_ord);
+    let mut scorer = weight.scorer(segment_reader, 1.0)?;
+    if scorer.doc() > doc_address.doc_id {
+        return Ok(None);
+    }
+    Ok((scorer.seek(doc_address.doc_id) == doc_address.doc_id).then(|| scorer.score()))
+}
+
+// 在词头词典中查找编辑距离以内的词项（与 tantivy 的 FuzzyTermQuery 内部使用的自动机相同）
+struct LevenshteinDfa<'a>(&'a DFA);
+
+impl Automaton for LevenshteinDfa<'_> {
+    type State = u32;
+
+    fn start(&self) -> u32 {
+        self.0.initial_state()
+    }
+
+    fn is_match(&self, state: &u32) -> bool {
+        matches!(self.0.distance(*state), Distance::Exact(_))
+    }
+
+    fn can_match(&self, state: &u32) -> bool {
+        *state != SINK_STATE
+    }
+
+    fn accept(&self, state: &u32, byte: u8) -> u32 {
+        self.0.transition(*state, byte)
+    }
+}
+
+// 编辑距离（相邻字符交换算一次编辑，与 FuzzyTermQuery 的 transposition 设置一致）
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut dist = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in dist[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            dist[i][j] = (dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1)
+                .min(dist[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                dist[i][j] = dist[i][j].min(dist[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    dist[a.len()][b.len()]
+}
+
+// 在阻塞线程中同步索引：对比清单中记录的路径、修改时间和内容哈希，
+// 只为新增、修改和删除的文件增删 tantivy 文档。cache 为 Dictionary 缓存的索引，全量重建替换目录时释放
+fn sync_index_blocking(
+    words_dir: &str,
+    index_path: &str,
+    schema: &Schema,
+    force_rebuild: bool,
+    settings: &BuildSettings,
+    control: &BuildControl,
+    cache: &RwLock<Option<Index>>,
+) -> Result<SyncSummary, String> {
+    // 确保 words 目录存在；词典包必须已经存在
+    let words_dir_path = Path::new(words_dir);
+    if pack::is_pack(words_dir_path) {
+        if !words_dir_path.is_file() {
+            return Err(format!("Dictionary pack not found: {}", words_dir));
+        }
+    } else if !words_dir_path.exists() {
+        fs::create_dir_all(words_dir).map_err(|e| format!("Failed to create words directory: {}", e))?;
+        eprintln!("Created words directory: {}", words_dir);
+    }
+
+    recover_interrupted_swap(index_path);
+
+    let started = Instant::now();
+    let mut summary = SyncSummary::default();
+
+    // 现有索引可用、schema 一致且清单完好时才做增量同步，否则全量重建
+    let existing = if force_rebuild {
+        None
+    } else {
+        open_index(index_path)
+            .ok()
+            .filter(|index| index.schema() == *schema)
+            .and_then(|index| IndexManifest::load(index_path).map(|m| (index, m)))
+    };
+
+    match existing {
+        Some((index, manifest)) => {
+            // 增量同步直接写入现有索引：tantivy 的提交是原子的，失败时保留上一次提交
+            apply_changes(words_dir, index_path, &index, manifest, settings, &mut summary, control)?;
+        }
+        None => {
+            summary.rebuilt = true;
+            rebuild_into_staging(words_dir, index_path, schema, settings, &mut summary, control, cache)?;
+        }
+    }
+
+    summary.elapsed_ms = started.elapsed().as_millis() as u64;
+    eprintln!(
+        "Index synced: {} added, {} updated, {} removed, {} unchanged ({} errors)",
+        summary.added, summary.updated, summary.removed, summary.unchanged, summary.errors
+    );
+
+    Ok(summary)
+}
+
+// 全量重建：在同级临时目录中构建新索引，校验通过后再替换现有索引，
+// 构建过程中旧索引保持完整可用
+fn rebuild_into_staging(
+    words_dir: &str,
+    index_path: &str,
+    schema: &Schema,
+    settings: &BuildSettings,
+    summary: &mut SyncSummary,
+    control: &BuildControl,
+    cache: &RwLock<Option<Index>>,
+) -> Result<(), String> {
+    let staging_path = sibling_path(index_path, "building");
+    let staging_dir = staging_path.to_string_lossy().to_string();
+
+    // 清理上次中断的构建留下的临时目录
+    if staging_path.exists() {
+        fs::remove_dir_all(&staging_path).map_err(|e| format!("Failed to remove staging directory: {}", e))?;
+    }
+    fs::create_dir_all(&staging_path).map_err(|e| format!("Failed to create staging directory: {}", e))?;
+
+    let result = (|| {
+        let index = create_index(&staging_path, schema.clone())
+            .map_err(|e| format!("Failed to create index: {}", e))?;
+        apply_changes(words_dir, &staging_dir, &index, IndexManifest::default(), settings, summary, control)?;
+        drop(index);
+        // 取消时不替换现有索引，临时目录在下面删除
+        control.check_cancelled()?;
+
+        verify_index(&staging_dir, schema, summary)?;
+
+        // 替换期间持有缓存的写锁：先关闭缓存的索引（Windows 上不能重命名有文件仍被映射的目录），
+        // 替换后再载入新索引，查询不会在两次改名之间重新打开旧索引
+        let mut cached = cache.write().unwrap_or_else(|e| e.into_inner());
+        *cached = None;
+        swap_into_place(&staging_path, Path::new(index_path))?;
+        *cached = open_index(index_path).ok();
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_dir_all(&staging_path);
+    }
+    result
+}
+
+// 校验新构建的索引：能打开、schema 一致、文档数与成功索引的文件数相同
+fn verify_index(staging_dir: &str, schema: &Schema, summary: &SyncSummary) -> Result<(), String> {
+    let index = open_index(staging_dir).map_err(|e| format!("New index cannot be opened: {}", e))?;
+    if index.schema() != *schema {
+        return Err("New index has an unexpected schema".to_string());
+    }
+
+    let reader = index.reader().map_err(|e| format!("New index cannot be read: {}", e))?;
+    let num_docs = reader.searcher().num_docs() as usize;
+    if num_docs != summary.added {
+        return Err(format!(
+            "New index has {} documents but {} files were indexed",
+            num_docs, summary.added
+        ));
+    }
+
+    // 所有文件都解析失败时不要用空索引替换现有词典
+    if num_docs == 0 && summary.total_files > 0 {
+        return Err(format!("None of the {} JSON files could be indexed", summary.total_files));
+    }
+
+    Ok(())
+}
+
+// 用新索引目录替换现有索引：旧索引先改名为备份，新目录改名到位后再删除备份
+fn swap_into_place(staging_path: &Path, index_path: &Path) -> Result<(), String> {
+    let backup_path = sibling_path(&index_path.to_string_lossy(), "old");
+    if backup_path.exists() {
+        fs::remove_dir_all(&backup_path).map_err(|e| format!("Failed to remove old index backup: {}", e))?;
+    }
+
+    if index_path.exists() {
+        fs::rename(index_path, &backup_path).map_err(|e| format!("Failed to move existing index aside: {}", e))?;
+    }
+
+    if let Err(e) = fs::rename(staging_path, index_path) {
+        // 放回旧索引
+        let _ = fs::rename(&backup_path, index_path);
+        return Err(format!("Failed to move new index into place: {}", e));
+    }
+
+    if backup_path.exists() && fs::remove_dir_all(&backup_path).is_err() {
+        eprintln!("Warning: Failed to remove old index backup {:?}", backup_path);
+    }
+
+    Ok(())
+}
+
+// 如果上次替换在两次改名之间中断（索引目录缺失但备份还在），恢复备份
+fn recover_interrupted_swap(index_path: &str) {
+    let backup_path = sibling_path(index_path, "old");
+    if !Path::new(index_path).exists() && backup_path.exists() {
+        match fs::rename(&backup_path, index_path) {
+            Ok(()) => eprintln!("Restored index from interrupted rebuild: {}", index_path),
+            Err(e) => eprintln!("Warning: Failed to restore index backup {:?}: {}", backup_path, e),
+        }
+    }
+}
+
+// 索引目录的同级路径，例如 words/.index -> words/.index.building
+fn sibling_path(index_path: &str, suffix: &str) -> PathBuf {
+    PathBuf::from(format!("{}.{}", index_path.trim_end_matches(['/', '\\']), suffix))
+}
+
+// 将 words 目录与清单对比，把变化写入给定索引，并将新清单保存到 manifest_dir。
+// 文件按批在线程池中并行读取和解析，再按原顺序写入 IndexWriter
+fn apply_changes(
+    words_dir: &str,
+    manifest_dir: &str,
+    index: &Index,
+    manifest: IndexManifest,
+    settings: &BuildSettings,
+    summary: &mut SyncSummary,
+    control: &BuildControl,
+) -> Result<(), String> {
+    if pack::is_pack(Path::new(words_dir)) {
+        return apply_pack_changes(words_dir, manifest_dir, index, manifest, settings, summary, control);
+    }
+
+    eprintln!("Syncing index from words directory: {}", words_dir);
+    let mut updater = IndexUpdater::new(index, manifest, settings)?;
+    let pool = settings.thread_pool()?;
+
+    let mut seen = HashSet::new();
+
+    // 遍历 words 目录及其子目录下的所有 JSON 文件
+    let files = scan::word_files(Path::new(words_dir))?;
+    let total = files.len();
+    control.report(0, total);
+    for (batch_number, batch) in files.chunks(BUILD_BATCH_SIZE).enumerate() {
+        if let Err(e) = control.check_cancelled() {
+            updater.rollback();
+            return Err(e);
+        }
+        control.report(batch_number * BUILD_BATCH_SIZE, total);
+
+        let prepared: Vec<PreparedEntry> = pool.install(|| {
+            batch
+                .par_iter()
+                .map(|file| updater.prepare_file(&file.rel_path, &file.path))
+                .collect()
+        });
+        for (file, entry) in batch.iter().zip(prepared) {
+            summary.total_files += 1;
+            *summary
+                .directories
+                .entry(scan::directory_of(&file.rel_path).to_string())
+                .or_insert(0) += 1;
+
+            seen.insert(file.rel_path.clone());
+            let origin = EntryOrigin { file: &file.path.to_string_lossy(), line: None };
+            updater.apply(&file.rel_path, origin, entry, summary);
+        }
+    }
+
+    // 清单中有但磁盘上已不存在的文件，删除对应文档
+    let removed: Vec<String> = updater
+        .manifest
+        .entries
+        .keys()
+        .chain(updater.manifest.failed.keys())
+        .filter(|rel_path| !seen.contains(*rel_path))
+        .cloned()
+        .collect();
+    for rel_path in removed {
+        updater.remove_file(&rel_path, summary);
+    }
+
+    control.report(total, total);
+    updater.report_duplicates(words_dir, false, summary);
+    updater.finish(manifest_dir)
+}
+
+// 词典包中用作词头的字段，只为取得清单中的键，不解析整个词条
+#[derive(serde::Deserialize)]
+struct PackHeadword {
+    word: String,
+}
+
+// 将词典包与清单对比：每行一个词条，以词头为键（同一词头再次出现时加 #2、#3 等），
+// 只为内容变化的行增删文档；包文件没有变化时不读取，只重复报告清单中记录的无法解析的行。
+// 包按批读取，每批先并行取出词头，按顺序确定键后再并行解析
+fn apply_pack_changes(
+    pack_path: &str,
+    manifest_dir: &str,
+    index: &Index,
+    manifest: IndexManifest,
+    settings: &BuildSettings,
+    summary: &mut SyncSummary,
+    control: &BuildControl,
+) -> Result<(), String> {
+    eprintln!("Syncing index from dictionary pack: {}", pack_path);
+    let metadata = fs::metadata(pack_path).map_err(|e| format!("Failed to read dictionary pack: {}", e))?;
+    let state = PackState {
+        mtime_ns: manifest::mtime_ns(&metadata),
+        size: metadata.len(),
+    };
+    if manifest.pack.as_ref() == Some(&state) && !manifest.entries.is_empty() {
+        summary.total_files = manifest.entries.len() + manifest.failed.len();
+        summary.unchanged = manifest.entries.len();
+        for failed in manifest.failed.into_values() {
+            summary.skip_issue(failed.skipped, failed.issue);
+        }
+        return Ok(());
+    }
+
+    let mut updater = IndexUpdater::new(index, manifest, settings)?;
+    // 包有变化时重新读取所有行，上次失败的行重新解析
+    updater.manifest.failed.clear();
+    let pool = settings.thread_pool()?;
+    let reader = pack::open_pack(Path::new(pack_path)).map_err(|e| format!("Failed to open dictionary pack: {}", e))?;
+    let mut lines = reader.lines().enumerate();
+
+    let mut seen = HashSet::new();
+    let mut occurrences: HashMap<String, usize> = HashMap::new();
+    loop {
+        if let Err(e) = control.check_cancelled() {
+            updater.rollback();
+            return Err(e);
+        }
+        control.report(summary.total_files, 0);
+
+        // 非空行及其行号
+        let mut batch = Vec::with_capacity(BUILD_BATCH_SIZE);
+        for (number, line) in lines.by_ref() {
+            let line = line.map_err(|e| format!("Failed to read dictionary pack: {}", e))?;
+            if !line.trim().is_empty() {
+                batch.push((number + 1, line));
+                if batch.len() == BUILD_BATCH_SIZE {
+                    break;
+                }
+            }
+        }
+        if batch.is_empty() {
+            break;
+        }
+
+        let headwords: Vec<_> = pool.install(|| {
+            batch
+                .par_iter()
+                .map(|(_, line)| serde_json::from_str::<PackHeadword>(line))
+                .collect()
+        });
+        let mut entries = Vec::with_capacity(batch.len());
+        for ((number, line), headword) in batch.into_iter().zip(headwords) {
+            summary.total_files += 1;
+            let origin = EntryOrigin { file: pack_path, line: Some(number) };
+            let word = match headword {
+                Ok(headword) => headword.word,
+                Err(e) => {
+                    // 没有词头，以行号为键记入清单
+                    eprintln!("Warning: Failed to parse {}: {}", origin.label(), e);
+                    let issue = origin.issue(BuildIssueKind::ParseError, Some((e.line(), e.column())), e.to_string());
+                    updater.record_failure(&format!("line {}", number), &origin, state.mtime_ns, line.len() as u64, issue.clone());
+                    summary.skip_issue(origin.label(), issue);
+                    continue;
+                }
+            };
+            let count = occurrences.entry(word.clone()).or_insert(0);
+            *count += 1;
+            let key = if *count == 1 { word } else { format!("{}#{}", word, count) };
+            seen.insert(key.clone());
+            entries.push((key, number, line));
+        }
+
+        let prepared: Vec<PreparedEntry> = pool.install(|| {
+            entries
+                .par_iter_mut()
+                .map(|(key, _, line)| {
+                    let size = line.len() as u64;
+                    updater.prepare_data(key, std::mem::take(line), state.mtime_ns, size)
+                })
+                .collect()
+        });
+        for ((key, number, _), entry) in entries.iter().zip(prepared) {
+            let origin = EntryOrigin { file: pack_path, line: Some(*number) };
+            updater.apply(key, origin, entry, summary);
+        }
+    }
+
+    let removed: Vec<String> = updater
+        .manifest
+        .entries
+        .keys()
+        .filter(|key| !seen.contains(*key))
+        .cloned()
+        .collect();
+    for key in removed {
+        updater.remove_file(&key, summary);
+    }
+
+    updater.manifest.pack = Some(state);
+    updater.report_duplicates(pack_path, true, summary);
+    updater.finish(manifest_dir)
+}
+
+// 只重新索引指定的文件（由文件监视器触发）；索引或清单不可用时退回完整同步
+fn reindex_files_blocking(
+    words_dir: &str,
+    index_path: &str,
+    schema: &Schema,
+    settings: &BuildSettings,
+    paths: &[PathBuf],
+    cache: &RwLock<Option<Index>>,
+) -> Result<SyncSummary, String> {
+    let existing = open_index(index_path)
+        .ok()
+        .filter(|index| index.schema() == *schema)
+        .and_then(|index| IndexManifest::load(index_path).map(|m| (index, m)));
+
+    // 词典包只能整体同步
+    let Some((index, manifest)) = existing.filter(|_| !pack::is_pack(Path::new(words_dir))) else {
+        return sync_index_blocking(words_dir, index_path, schema, false, settings, &BuildControl::default(), cache);
+    };
+
+    // 监视器给出的是绝对路径，words 目录可能是相对路径
+    let words_dir_path = Path::new(words_dir).canonicalize().unwrap_or_else(|_| PathBuf::from(words_dir));
+
+    // 忽略文件变化、目录被创建、改名或删除时，受影响的文件无法逐个确定，做一次完整同步。
+    // 已消失的路径只有在清单中有它下面的文件时才是目录，编辑器和 sed -i 的临时文件（4913、sedXXXXXX）不算
+    let needs_full_sync = paths.iter().any(|path| {
+        path.file_name().is_some_and(|name| name == scan::IGNORE_FILE)
+            || path.is_dir()
+            || (!path.exists() && is_indexed_directory(&manifest, &words_dir_path, path))
+    });
+    if needs_full_sync {
+        // 完整同步可能重建并替换索引目录，先关闭这里打开的索引
+        drop(index);
+        return sync_index_blocking(words_dir, index_path, schema, false, settings, &BuildControl::default(), cache);
+    }
+
+    let mut summary = SyncSummary::default();
+    let mut files: Vec<(String, &PathBuf)> = paths
+        .iter()
+        .filter(|path| scan::is_word_file(path))
+        .filter_map(|path| scan::rel_path(&words_dir_path, path).map(|rel_path| (rel_path, path)))
+        .collect();
+    files.sort();
+    files.dedup_by(|a, b| a.0 == b.0);
+    if files.is_empty() {
+        return Ok(summary);
+    }
+
+    let mut updater = IndexUpdater::new(&index, manifest, settings)?;
+
+    for (rel_path, path) in files {
+        if path.is_file() && !scan::is_ignored(&words_dir_path, path) {
+            summary.total_files += 1;
+            updater.sync_file(&rel_path, path, &mut summary);
+        } else if updater.manifest.contains(&rel_path) {
+            updater.remove_file(&rel_path, &mut summary);
+        }
+    }
+
+    updater.finish(index_path)?;
+
+    summary.changed_words.sort();
+    summary.changed_words.dedup();
+    Ok(summary)
+}
+
+// 清单中是否有位于该目录下的文件（目录已被删除或改名时，据此与同名的临时文件区分）
+fn is_indexed_directory(manifest: &IndexManifest, words_dir: &Path, path: &Path) -> bool {
+    let Some(rel_path) = scan::rel_path(words_dir, path) else {
+        return false;
+    };
+    let prefix = format!("{}/", rel_path);
+    let has_prefix = |key: Option<&String>| key.is_some_and(|key| key.starts_with(&prefix));
+    has_prefix(manifest.entries.range(prefix.clone()..).next().map(|(key, _)| key))
+        || has_prefix(manifest.failed.range(prefix.clone()..).next().map(|(key, _)| key))
+}
+
+// 词条来自哪里：词条文件的路径，或词典包的路径和行号
+struct EntryOrigin<'a> {
+    file: &'a str,
+    line: Option<usize>,
+}
+
+impl EntryOrigin<'_> {
+    fn label(&self) -> String {
+        match self.line {
+            Some(line) => format!("{}:{}", self.file, line),
+            None => self.file.to_string(),
+        }
+    }
+
+    // position 为 serde_json 给出的 (行, 列)，词典包中的行号仍以包的行为准
+    fn issue(&self, kind: BuildIssueKind, position: Option<(usize, usize)>, message: String) -> BuildIssue {
+        let (line, column) = match (self.line, position) {
+            (Some(line), Some((_, column))) => (Some(line), Some(column)),
+            (None, Some((line, column))) => (Some(line), Some(column)),
+            (line, None) => (line, None),
+        };
+        BuildIssue {
+            kind,
+            file: self.file.to_string(),
+            line,
+            column,
+            message,
+        }
+    }
+}
+
+// 在线程池中读取并解析好的词条，按原顺序交给 IndexUpdater::apply 写入索引
+enum PreparedEntry {
+    // 修改时间和大小都没变，没有读取文件
+    Unchanged,
+    // 内容哈希没变（例如只是被 touch 过），只需更新清单中的修改时间
+    Touched { mtime_ns: u64, size: u64 },
+    // 内容有变化：解析出的词头和文档，或解析错误的行列号和原因
+    Changed {
+        mtime_ns: u64,
+        size: u64,
+        hash: String,
+        parsed: Result<(String, TantivyDocument), ((usize, usize), String)>,
+    },
+    // 上次同步时失败、此后没有变化的文件，重复报告清单中记录的原因
+    StillFailing(FailedEntry),
+    // 无法读取文件；读取内容失败时有修改时间和大小，可以记入清单
+    Unreadable { state: Option<(u64, u64)>, message: String },
+}
+
+// 持有 IndexWriter 和清单，按文件增删文档
+struct IndexUpdater {
+    writer: tantivy::IndexWriter,
+    manifest: IndexManifest,
+    word_field: Field,
+    headword_field: Field,
+    concise_definition_field: Field,
+    json_data_field: Field,
+    forms_field: Field,
+    concise_cn_field: Field,
+    explanation_cn_field: Field,
+    explanation_en_field: Field,
+    example_en_field: Field,
+    analysis_field: Field,
+    path_field: Field,
+}
+
+impl IndexUpdater {
+    fn new(index: &Index, manifest: IndexManifest, settings: &BuildSettings) -> Result<Self, String> {
+        let writer = index.writer(settings.writer_memory)
+            .map_err(|e| format!("Failed to create index writer: {}", e))?;
+
+        let schema = index.schema();
+
+        let word_field = schema.get_field("word")
+            .map_err(|e| format!("Failed to get word field: {}", e))?;
+        let headword_field = schema.get_field("headword")
+            .map_err(|e| format!("Failed to get headword field: {}", e))?;
+        let concise_definition_field = schema.get_field("concise_definition")
+            .map_err(|e| format!("Failed to get concise_definition field: {}", e))?;
+        let json_data_field = schema.get_field("json_data")
+            .map_err(|e| format!("Failed to get json_data field: {}", e))?;
+        let forms_field = schema.get_field("forms")
+            .map_err(|e| format!("Failed to get forms field: {}", e))?;
+        let concise_cn_field = schema.get_field("concise_cn")
+            .map_err(|e| format!("Failed to get concise_cn field: {}", e))?;
+        let explanation_cn_field = schema.get_field("explanation_cn")
+            .map_err(|e| format!("Failed to get explanation_cn field: {}", e))?;
+        let explanation_en_field = schema.get_field("explanation_en")
+            .map_err(|e| format!("Failed to get explanation_en field: {}", e))?;
+        let example_en_field = schema.get_field("example_en")
+            .map_err(|e| format!("Failed to get example_en field: {}", e))?;
+        let analysis_field = schema.get_field("analysis")
+            .map_err(|e| format!("Failed to get analysis field: {}", e))?;
+        let path_field = schema.get_field("path")
+            .map_err(|e| format!("Failed to get path field: {}", e))?;
+
+        Ok(IndexUpdater {
+            writer,
+            manifest,
+            word_field,
+            headword_field,
+            concise_definition_field,
+            json_data_field,
+            forms_field,
+            concise_cn_field,
+            explanation_cn_field,
+            explanation_en_field,
+            example_en_field,
+            analysis_field,
+            path_field,
+        })
+    }
+
+    // 读取并同步单个文件（文件监视器触发的重新索引）
+    fn sync_file(&mut self, rel_path: &str, path: &Path, summary: &mut SyncSummary) {
+        let entry = self.prepare_file(rel_path, path);
+        let origin = EntryOrigin { file: &path.to_string_lossy(), line: None };
+        self.apply(rel_path, origin, entry, summary);
+    }
+
+    // 按修改时间和大小判断文件是否变化，变化时读取并解析；只读取清单，可以在多个线程中同时调用
+    fn prepare_file(&self, rel_path: &str, path: &Path) -> PreparedEntry {
+        let metadata = match fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) => return PreparedEntry::Unreadable { state: None, message: e.to_string() },
+        };
+        let mtime_ns = manifest::mtime_ns(&metadata);
+        let size = metadata.len();
+
+        // 修改时间和大小都没变，直接跳过，不读取文件
+        if self.manifest.recorded_state(rel_path) == Some((mtime_ns, size)) {
+            return match self.manifest.failed.get(rel_path) {
+                Some(failed) => PreparedEntry::StillFailing(failed.clone()),
+                None => PreparedEntry::Unchanged,
+            };
+        }
+
+        match fs::read_to_string(path) {
+            Ok(data) => self.prepare_data(rel_path, data, mtime_ns, size),
+            Err(e) => PreparedEntry::Unreadable { state: Some((mtime_ns, size)), message: e.to_string() },
+        }
+    }
+
+    // 按内容哈希判断词条是否变化，变化时解析 JSON 并生成文档
+    fn prepare_data(&self, rel_path: &str, data: String, mtime_ns: u64, size: u64) -> PreparedEntry {
+        let hash = manifest::content_hash(data.as_bytes());
+        if self.manifest.entries.get(rel_path).is_some_and(|prev| prev.hash == hash) {
+            return PreparedEntry::Touched { mtime_ns, size };
+        }
+
+        // 解析 JSON 以获取单词和简明释义
+        let parsed = match serde_json::from_str::<WordDefinition>(&data) {
+            Ok(word_def) => {
+                let concise = word_def
+                    .concise_definition
+                    .clone()
+                    .unwrap_or_default();
+
+                let mut doc = tantivy::doc!(
+                    self.word_field => word_def.word.clone(),
+                    self.headword_field => normalize_headword(&word_def.word),
+                    self.concise_definition_field => concise.clone(),
+                    self.concise_cn_field => concise,
+                    self.json_data_field => data,
+                    self.path_field => rel_path
+                );
+                for meaning in word_def.meanings.iter().flatten() {
+                    doc.add_text(self.explanation_en_field, &meaning.explanation_en);
+                    if let Some(explanation_cn) = &meaning.explanation_cn {
+                        doc.add_text(self.explanation_cn_field, explanation_cn);
+                    }
+                    if let Some(example_en) = &meaning.example_en {
+                        doc.add_text(self.example_en_field, example_en);
+                    }
+                }
+                for analysis in word_def.comparisons.iter().flatten().filter_map(|c| c.analysis.as_deref()) {
+                    doc.add_text(self.analysis_field, analysis);
+                }
+                for form in word_def.inflected_forms().into_keys() {
+                    doc.add_text(self.forms_field, &form);
+                }
+                Ok((word_def.word, doc))
+            }
+            Err(e) => Err(((e.line(), e.column()), e.to_string())),
+        };
+        PreparedEntry::Changed { mtime_ns, size, hash, parsed }
+    }
+
+    // 把准备好的词条写入索引并更新清单；origin 用于警告和构建报告
+    fn apply(&mut self, rel_path: &str, origin: EntryOrigin<'_>, entry: PreparedEntry, summary: &mut SyncSummary) {
+        let (mtime_ns, size, hash, parsed) = match entry {
+            PreparedEntry::Unchanged => {
+                summary.unchanged += 1;
+                return;
+            }
+            PreparedEntry::Touched { mtime_ns, size } => {
+                if let Some(prev) = self.manifest.entries.get_mut(rel_path) {
+                    prev.mtime_ns = mtime_ns;
+                    prev.size = size;
+                }
+                summary.unchanged += 1;
+                return;
+            }
+            PreparedEntry::StillFailing(failed) => {
+                summary.skip_issue(failed.skipped, failed.issue);
+                return;
+            }
+            PreparedEntry::Unreadable { state, message } => {
+                eprintln!("Warning: Failed to read {}: {}", origin.label(), message);
+                let issue = origin.issue(BuildIssueKind::Unreadable, None, message);
+                if let Some((mtime_ns, size)) = state {
+                    self.record_failure(rel_path, &origin, mtime_ns, size, issue.clone());
+                }
+                summary.skip_issue(origin.label(), issue);
+                return;
+            }
+            PreparedEntry::Changed { mtime_ns, size, hash, parsed } => (mtime_ns, size, hash, parsed),
+        };
+
+        // 先按路径删除旧文档，保证重复同步是幂等的
+        self.writer.delete_term(Term::from_field_text(self.path_field, rel_path));
+        self.manifest.failed.remove(rel_path);
+        let previous = self.manifest.entries.remove(rel_path);
+        if let Some(prev) = &previous {
+            summary.changed_words.push(prev.word.clone());
+        }
+
+        match parsed {
+            Ok((word, doc)) => {
+                if let Err(e) = self.writer.add_document(doc) {
+                    eprintln!("Warning: Failed to add document for {}: {}", origin.label(), e);
+                    summary.skip(BuildIssueKind::IndexError, &origin, None, e.to_string());
+                } else {
+                    if previous.is_some() {
+                        summary.updated += 1;
+                    } else {
+                        summary.added += 1;
+                    }
+                    summary.changed_words.push(word.clone());
+                    self.manifest.entries.insert(rel_path.to_string(), ManifestEntry { mtime_ns, size, hash, word });
+                }
+            }
+            Err((position, message)) => {
+                // 文件变为无效 JSON：旧文档已删除，文件再次变化时重新解析
+                eprintln!("Warning: Failed to parse JSON file {}: {}", origin.label(), message);
+                let issue = origin.issue(BuildIssueKind::ParseError, Some(position), message);
+                self.record_failure(rel_path, &origin, mtime_ns, size, issue.clone());
+                summary.skip_issue(origin.label(), issue);
+            }
+        }
+    }
+
+    // 在清单中记录失败的文件，文件没有变化时不再读取，也不会被当作索引过期
+    fn record_failure(&mut self, rel_path: &str, origin: &EntryOrigin<'_>, mtime_ns: u64, size: u64, issue: BuildIssue) {
+        self.manifest.failed.insert(
+            rel_path.to_string(),
+            FailedEntry { mtime_ns, size, skipped: origin.label(), issue },
+        );
+    }
+
+    // 按清单中的词头找出重复的词条；files_root 为 words 目录（词典包为包文件）
+    fn report_duplicates(&self, files_root: &str, is_pack: bool, summary: &mut SyncSummary) {
+        let mut first_seen: HashMap<String, &str> = HashMap::new();
+        for (rel_path, entry) in &self.manifest.entries {
+            let headword = normalize_headword(&entry.word);
+            let file = |rel_path: &str| {
+                if is_pack {
+                    files_root.to_string()
+                } else {
+                    Path::new(files_root).join(rel_path).to_string_lossy().into_owned()
+                }
+            };
+            match first_seen.get(&headword) {
+                Some(first) => summary.issues.push(BuildIssue {
+                    kind: BuildIssueKind::DuplicateHeadword,
+                    file: file(rel_path),
+                    line: None,
+                    column: None,
+                    message: if is_pack {
+                        format!("\"{}\" is defined more than once", entry.word)
+                    } else {
+                        format!("\"{}\" is also defined in {}", entry.word, file(first))
+                    },
+                }),
+                None => {
+                    first_seen.insert(headword, rel_path);
+                }
+            }
+        }
+    }
+
+    // 放弃未提交的修改（构建被取消），索引保持上一次提交的状态，清单不保存
+    fn rollback(mut self) {
+        if let Err(e) = self.writer.rollback() {
+            eprintln!("Warning: Failed to roll back index changes: {}", e);
+        }
+    }
+
+    // 文件已从磁盘删除，删除对应文档
+    fn remove_file(&mut self, rel_path: &str, summary: &mut SyncSummary) {
+        // 上次就没能建立索引的文件没有文档
+        if self.manifest.failed.remove(rel_path).is_some() {
+            return;
+        }
+        self.writer.delete_term(Term::from_field_text(self.path_field, rel_path));
+        if let Some(prev) = self.manifest.entries.remove(rel_path) {
+            summary.changed_words.push(prev.word);
+        }
+        summary.removed += 1;
+    }
+
+    // 提交并等待合并完成，然后保存清单和自动完成 FST
+    fn finish(mut self, manifest_dir: &str) -> Result<(), String> {
+        self.writer.commit().map_err(|e| format!("Failed to commit index: {}", e))?;
+        self.writer
+            .wait_merging_threads()
+            .map_err(|e| format!("Failed to finish index merges: {}", e))?;
+        self.manifest.save(manifest_dir).map_err(|e| format!("Failed to write index manifest: {}", e))?;
+        Autocomplete::build(self.manifest.entries.values().map(|entry| entry.word.as_str()), manifest_dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    fn write_word(words_dir: &Path, rel_path: &str, word: &str, concise: &str) {
+        let path = words_dir.join(rel_path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let entry = serde_json::json!({ "word": word, "concise_definition": concise });
+        fs::write(path, entry.to_string()).unwrap();
+    }
+
+    // 把文件的修改时间往后推，模拟编辑器保存或 touch
+    fn bump_mtime(path: &Path) {
+        let file = fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(60)).unwrap();
+    }
+
+    fn sync(words_dir: &Path, index_dir: &Path) -> SyncSummary {
+        sync_index_blocking(
+            &words_dir.to_string_lossy(),
+            &index_dir.to_string_lossy(),
+            &build_schema(),
+            false,
+            &BuildSettings { writer_memory: MIN_WRITER_MEMORY, threads: 1 },
+            &BuildControl::default(),
+            &RwLock::new(None),
+        )
+        .unwrap()
+    }
+
+    fn changed_words(summary: &SyncSummary) -> BTreeSet<&str> {
+        summary.changed_words.iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn first_sync_adds_every_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let words_dir = temp.path().join("words");
+        write_word(&words_dir, "give.json", "give", "v. 给");
+        write_word(&words_dir, "ta/take.json", "take", "v. 拿");
+
+        let summary = sync(&words_dir, &temp.path().join("index"));
+        assert!(summary.rebuilt);
+        assert_eq!((summary.added, summary.updated, summary.removed, summary.unchanged), (2, 0, 0, 0));
+
+        let manifest = IndexManifest::load(&temp.path().join("index").to_string_lossy()).unwrap();
+        assert_eq!(manifest.entries.keys().map(String::as_str).collect::<Vec<_>>(), ["give.json", "ta/take.json"]);
+        assert_eq!(manifest.entries["ta/take.json"].word, "take");
+    }
+
+    #[test]
+    fn classifies_added_updated_removed_and_unchanged_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let words_dir = temp.path().join("words");
+        let index_dir = temp.path().join("index");
+        write_word(&words_dir, "give.json", "give", "v. 给");
+        write_word(&words_dir, "take.json", "take", "v. 拿");
+        write_word(&words_dir, "keep.json", "keep", "v. 保持");
+        sync(&words_dir, &index_dir);
+
+        write_word(&words_dir, "make.json", "make", "v. 做");
+        write_word(&words_dir, "take.json", "take", "v. 拿走；取");
+        fs::remove_file(words_dir.join("keep.json")).unwrap();
+
+        let summary = sync(&words_dir, &index_dir);
+        assert!(!summary.rebuilt);
+        assert_eq!((summary.added, summary.updated, summary.removed, summary.unchanged), (1, 1, 1, 1));
+        assert_eq!(changed_words(&summary), BTreeSet::from(["keep", "make", "take"]));
+
+        let manifest = IndexManifest::load(&index_dir.to_string_lossy()).unwrap();
+        assert_eq!(
+            manifest.entries.keys().map(String::as_str).collect::<Vec<_>>(),
+            ["give.json", "make.json", "take.json"]
+        );
+
+        let dictionary = Dictionary::with_index_path(
+            words_dir.to_string_lossy().into_owned(),
+            index_dir.to_string_lossy().into_owned(),
+        );
+        assert_eq!(dictionary.list_words().unwrap(), ["give", "make", "take"]);
+        assert_eq!(dictionary.lookup_word("take").unwrap().unwrap().concise_definition.as_deref(), Some("v. 拿走；取"));
+        assert!(dictionary.lookup_word("keep").unwrap().is_none());
+    }
+
+    #[test]
+    fn rebuild_closes_and_reloads_cached_index() {
+        let temp = tempfile::tempdir().unwrap();
+        let words_dir = temp.path().join("words");
+        let index_dir = temp.path().join("index");
+        write_word(&words_dir, "give.json", "give", "v. 给");
+        sync(&words_dir, &index_dir);
+
+        let cache = RwLock::new(Some(open_index(&index_dir.to_string_lossy()).unwrap()));
+        write_word(&words_dir, "take.json", "take", "v. 拿");
+        sync_index_blocking(
+            &words_dir.to_string_lossy(),
+            &index_dir.to_string_lossy(),
+            &build_schema(),
+            true,
+            &BuildSettings { writer_memory: MIN_WRITER_MEMORY, threads: 1 },
+            &BuildControl::default(),
+            &cache,
+        )
+        .unwrap();
+
+        // 缓存中是替换后的新索引
+        let cached = cache.read().unwrap();
+        let reader = cached.as_ref().unwrap().reader().unwrap();
+        assert_eq!(reader.searcher().num_docs(), 2);
+        assert!(!sibling_path(&index_dir.to_string_lossy(), "old").exists());
+    }
+
+    #[test]
+    fn cancelled_build_reports_build_cancelled() {
+        let temp = tempfile::tempdir().unwrap();
+        let words_dir = temp.path().join("words");
+        write_word(&words_dir, "give.json", "give", "v. 给");
+        let dictionary = Dictionary::with_index_path(
+            words_dir.to_string_lossy().into_owned(),
+            temp.path().join("index").to_string_lossy().into_owned(),
+        );
+
+        let control = BuildControl::default();
+        control.cancel.cancel();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let error = runtime.block_on(dictionary.build_index_async(control)).unwrap_err();
+        assert!(error.is::<BuildCancelled>());
+        assert!(!temp.path().join("index").exists());
+    }
+
+    #[test]
+    fn errors_before_cancellation_are_not_reported_as_cancelled() {
+        let temp = tempfile::tempdir().unwrap();
+        let pack = temp.path().join("missing.jsonl");
+        let dictionary = Dictionary::new(pack.to_string_lossy().into_owned());
+
+        let control = BuildControl::default();
+        control.cancel.cancel();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let error = runtime.block_on(dictionary.build_index_async(control)).unwrap_err();
+        assert!(!error.is::<BuildCancelled>());
+        assert!(error.to_string().contains("Dictionary pack not found"));
+    }
+
+    #[test]
+    fn comparison_analysis_is_stemmed_like_explanations() {
+        let temp = tempfile::tempdir().unwrap();
+        let words_dir = temp.path().join("words");
+        let index_dir = temp.path().join("index");
+        let entry = serde_json::json!({
+            "word": "desert",
+            "comparison": [{ "word_to_compare": "abandon", "analysis": "Desert stresses leaving a duty; abandon stresses giving up entirely." }]
+        });
+        fs::create_dir_all(&words_dir).unwrap();
+        fs::write(words_dir.join("desert.json"), entry.to_string()).unwrap();
+        sync(&words_dir, &index_dir);
+
+        let dictionary = Dictionary::with_index_path(
+            words_dir.to_string_lossy().into_owned(),
+            index_dir.to_string_lossy().into_owned(),
+        );
+        let hits = dictionary.full_text_search("abandoning", 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].word.as_str(), hits[0].field), ("desert", MatchedField::Comparison));
+    }
+
+    #[test]
+    fn edit_distance_counts_single_character_edits() {
+        assert_eq!(edit_distance("give", "give"), 0);
+        assert_eq!(edit_distance("give", "gave"), 1);
+        assert_eq!(edit_distance("give", "giver"), 1);
+        assert_eq!(edit_distance("give", "gie"), 1);
+        // 相邻两个字母互换算一次编辑
+        assert_eq!(edit_distance("give", "gvie"), 1);
+        assert_eq!(edit_distance("abandon", "abnadon"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        // 按字符而不是字节计算
+        assert_eq!(edit_distance("放弃", "抛弃"), 1);
+    }
+
+    fn write_senses(words_dir: &Path, word: &str, senses: usize) {
+        let meanings: Vec<_> = (0..senses)
+            .map(|i| serde_json::json!({ "pos": "noun", "explanation_en": format!("sense {}", i) }))
+            .collect();
+        let entry = serde_json::json!({ "word": word, "definitions": meanings });
+        fs::create_dir_all(words_dir).unwrap();
+        fs::write(words_dir.join(format!("{}.json", word)), entry.to_string()).unwrap();
+    }
+
+    #[test]
+    fn suggestions_rank_by_distance_then_sense_count_then_limit() {
+        let temp = tempfile::tempdir().unwrap();
+        let words_dir = temp.path().join("words");
+        let index_dir = temp.path().join("index");
+        for (word, senses) in [("band", 1), ("bane", 3), ("bent", 2), ("bait", 2), ("banter", 5), ("bandit", 1), ("zebra", 9)] {
+            write_senses(&words_dir, word, senses);
+        }
+        sync(&words_dir, &index_dir);
+
+        let dictionary = Dictionary::with_index_path(
+            words_dir.to_string_lossy().into_owned(),
+            index_dir.to_string_lossy().into_owned(),
+        );
+        // 距离 1：bane（3 个义项）、bait 与 bent（2 个，按字母）、band；距离 2：banter、bandit
+        assert_eq!(
+            dictionary.suggest_similar("Bant", 10).unwrap(),
+            ["bane", "bait", "bent", "band", "banter", "bandit"]
+        );
+        assert_eq!(dictionary.suggest_similar("bant", 3).unwrap(), ["bane", "bait", "bent"]);
+        assert_eq!(dictionary.suggest_similar("bant", 5).unwrap(), ["bane", "bait", "bent", "band", "banter"]);
+        // 完全相同的词头不作为建议
+        assert_eq!(dictionary.suggest_similar("band", 1).unwrap(), ["bane"]);
+        assert!(dictionary.suggest_similar("qqqq", 5).unwrap().is_empty());
+    }
+
+    fn full_text_dictionary(temp: &tempfile::TempDir) -> Dictionary {
+        let words_dir = temp.path().join("words");
+        let index_dir = temp.path().join("index");
+        fs::create_dir_all(&words_dir).unwrap();
+        let entries = [
+            serde_json::json!({
+                "word": "abandon",
+                "definitions": [{ "pos": "verb", "explanation_en": "To leave a place, thing or person forever.",
+                                  "example_en": "The captain gave the order to abandon ship." }]
+            }),
+            serde_json::json!({
+                "word": "vessel",
+                "definitions": [{ "pos": "noun", "explanation_en": "A ship or large boat; any ship that carries cargo." }]
+            }),
+            serde_json::json!({
+                "word": "desert",
+                "definitions": [{ "pos": "verb", "explanation_en": "To leave without help or support." }],
+                "comparison": [{ "word_to_compare": "abandon", "analysis": "Sailors desert a sinking ship to save themselves." }]
+            }),
+        ];
+        for entry in entries {
+            let word = entry["word"].as_str().unwrap().to_string();
+            fs::write(words_dir.join(format!("{}.json", word)), entry.to_string()).unwrap();
+        }
+        sync(&words_dir, &index_dir);
+        Dictionary::with_index_path(
+            words_dir.to_string_lossy().into_owned(),
+            index_dir.to_string_lossy().into_owned(),
+        )
+    }
+
+    fn highlighted(hit: &FullTextHit) -> Vec<&str> {
+        hit.highlights.iter().map(|range| &hit.snippet[range.clone()]).collect()
+    }
+
+    #[test]
+    fn full_text_hits_report_field_snippet_and_score_order() {
+        let temp = tempfile::tempdir().unwrap();
+        let dictionary = full_text_dictionary(&temp);
+
+        let hits = dictionary.full_text_search("ship", 10).unwrap();
+        let found: Vec<(&str, MatchedField)> = hits.iter().map(|hit| (hit.word.as_str(), hit.field)).collect();
+        // vessel 的释义中出现两次，得分最高
+        assert_eq!(found[0], ("vessel", MatchedField::Explanation));
+        assert_eq!(found.len(), 3);
+        assert!(found.contains(&("abandon", MatchedField::Example)));
+        assert!(found.contains(&("desert", MatchedField::Comparison)));
+        assert!(hits.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+        for hit in &hits {
+            assert!(!hit.highlights.is_empty());
+            assert!(highlighted(hit).iter().all(|text| text.eq_ignore_ascii_case("ship")));
+        }
+        assert_eq!(hits[0].snippet, "A ship or large boat; any ship that carries cargo");
+        assert_eq!(highlighted(&hits[0]), ["ship", "ship"]);
+    }
+
+    #[test]
+    fn full_text_reports_the_field_that_satisfies_the_whole_query() {
+        let temp = tempfile::tempdir().unwrap();
+        let dictionary = full_text_dictionary(&temp);
+
+        // 只有 abandon 的例句同时包含两个词
+        let hits = dictionary.full_text_search("abandon ship", 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].field, MatchedField::Example);
+        assert_eq!(highlighted(&hits[0]), ["abandon", "ship"]);
+
+        // 两个词分别在释义和例句中：没有字段单独满足查询，取高亮最多（相同时靠前）的字段
+        let hits = dictionary.full_text_search("forever ship", 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].word.as_str(), hits[0].field), ("abandon", MatchedField::Explanation));
+        assert_eq!(highlighted(&hits[0]), ["forever"]);
+
+        // 词干化：sinking 匹配 sink，leaving 匹配 leave
+        let hits = dictionary.full_text_search("sink", 10).unwrap();
+        assert_eq!(hits.iter().map(|hit| (hit.word.as_str(), hit.field)).collect::<Vec<_>>(), [("desert", MatchedField::Comparison)]);
+        let hits = dictionary.full_text_search("leaving", 10).unwrap();
+        assert!(hits.iter().all(|hit| hit.field == MatchedField::Explanation));
+        assert_eq!(hits.len(), 2);
+
+        assert!(dictionary.full_text_search("   ", 10).unwrap().is_empty());
+        assert!(dictionary.full_text_search("zeppelin", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn touched_file_with_same_content_is_unchanged() {
+        let temp = tempfile::tempdir().unwrap();
+        let words_dir = temp.path().join("words");
+        let index_dir = temp.path().join("index");
+        write_word(&words_dir, "give.json", "give", "v. 给");
+        sync(&words_dir, &index_dir);
+
+        bump_mtime(&words_dir.join("give.json"));
+        let summary = sync(&words_dir, &index_dir);
+        assert_eq!((summary.added, summary.updated, summary.removed, summary.unchanged), (0, 0, 0, 1));
+        assert!(summary.changed_words.is_empty());
+
+        // 清单记录新的修改时间，下次同步不再读取文件
+        let metadata = fs::metadata(words_dir.join("give.json")).unwrap();
+        let manifest = IndexManifest::load(&index_dir.to_string_lossy()).unwrap();
+        assert_eq!(manifest.recorded_state("give.json"), Some((manifest::mtime_ns(&metadata), metadata.len())));
+    }
+
+    #[test]
+    fn same_size_edit_is_detected_by_content_hash() {
+        let temp = tempfile::tempdir().unwrap();
+        let words_dir = temp.path().join("words");
+        let index_dir = temp.path().join("index");
+        write_word(&words_dir, "give.json", "give", "v. aaa");
+        sync(&words_dir, &index_dir);
+
+        write_word(&words_dir, "give.json", "give", "v. bbb");
+        bump_mtime(&words_dir.join("give.json"));
+        let summary = sync(&words_dir, &index_dir);
+        assert_eq!((summary.added, summary.updated, summary.removed, summary.unchanged), (0, 1, 0, 0));
+    }
+
+    #[test]
+    fn invalid_file_is_recorded_as_failed_until_fixed() {
+        let temp = tempfile::tempdir().unwrap();
+        let words_dir = temp.path().join("words");
+        let index_dir = temp.path().join("index");
+        write_word(&words_dir, "give.json", "give", "v. 给");
+        fs::write(words_dir.join("bad.json"), "{ \"word\": ").unwrap();
+
+        let summary = sync(&words_dir, &index_dir);
+        assert_eq!((summary.added, summary.errors), (1, 1));
+        let manifest = IndexManifest::load(&index_dir.to_string_lossy()).unwrap();
+        assert!(manifest.failed.contains_key("bad.json"));
+        assert!(!manifest.entries.contains_key("bad.json"));
+
+        // 没有变化的失败文件不算过期，只重复报告原因
+        let summary = sync(&words_dir, &index_dir);
+        assert_eq!((summary.added, summary.updated, summary.removed, summary.unchanged, summary.errors), (0, 0, 0, 1, 1));
+
+        write_word(&words_dir, "bad.json", "bad", "adj. 坏的");
+        let summary = sync(&words_dir, &index_dir);
+        assert_eq!((summary.added, summary.errors), (1, 0));
+        let manifest = IndexManifest::load(&index_dir.to_string_lossy()).unwrap();
+        assert!(manifest.failed.is_empty());
+        assert_eq!(manifest.entries["bad.json"].word, "bad");
+    }
+
+    #[test]
+    fn removing_a_failed_file_is_not_counted_as_removed() {
+        let temp = tempfile::tempdir().unwrap();
+        let words_dir = temp.path().join("words");
+        let index_dir = temp.path().join("index");
+        write_word(&words_dir, "give.json", "give", "v. 给");
+        fs::write(words_dir.join("bad.json"), "not json").unwrap();
+        sync(&words_dir, &index_dir);
+
+        fs::remove_file(words_dir.join("bad.json")).unwrap();
+        let summary = sync(&words_dir, &index_dir);
+        assert_eq!((summary.removed, summary.unchanged, summary.errors), (0, 1, 0));
+        assert!(IndexManifest::load(&index_dir.to_string_lossy()).unwrap().failed.is_empty());
+    }
+
+    #[test]
+    fn cached_index_sees_rebuilds_and_incremental_syncs() {
+        let temp = tempfile::tempdir().unwrap();
+        let words_dir = temp.path().join("words");
+        fs::create_dir_all(&words_dir).unwrap();
+        write_word(&words_dir, "give.json", "give", "v. 给");
+        let dictionary = Dictionary::with_index_path(
+            words_dir.to_string_lossy().into_owned(),
+            temp.path().join("index").to_string_lossy().into_owned(),
+        );
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        // 重建后缓存中已经是新索引
+        assert!(runtime.block_on(dictionary.rebuild_index_async()).unwrap().rebuilt);
+        assert!(dictionary.index.read().unwrap().is_some());
+        assert!(dictionary.lookup_word("give").unwrap().is_some());
+
+        write_word(&words_dir, "take.json", "take", "v. 拿");
+        let summary = runtime.block_on(dictionary.sync_index_async()).unwrap();
+        assert!(!summary.rebuilt);
+        assert!(dictionary.lookup_word("take").unwrap().is_some());
+
+        write_word(&words_dir, "keep.json", "keep", "v. 保持");
+        runtime.block_on(dictionary.rebuild_index_async()).unwrap();
+        assert!(dictionary.lookup_word("keep").unwrap().is_some());
+        assert_eq!(dictionary.complete_prefix("k", 10).unwrap(), ["keep"]);
+
+        // 文件监视器的增量重建：索引被删除后 reindex_files 会全量重建
+        fs::remove_dir_all(temp.path().join("index")).unwrap();
+        write_word(&words_dir, "hold.json", "hold", "v. 握住");
+        let summary = dictionary.reindex_files(&[words_dir.join("hold.json")]).unwrap();
+        assert!(summary.rebuilt);
+        assert!(dictionary.lookup_word("hold").unwrap().is_some());
+    }
 }
@@ -0,0 +1,319 @@
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+use crate::dictionary::{
+    edit_distance, BuildCancelled, BuildControl, Dictionary, FullTextHit, IndexStats, LookupResult, SyncSummary,
+};
+use crate::models::{normalize_headword, WordDefinition};
+
+// 同时加载的多个词典（如通用词典加上领域词汇表），每个词典有自己的 words 目录和索引。
+// 查询时按优先级合并各词典的结果；被禁用的词典不参与查询，但仍会同步索引。
+pub struct DictionarySet {
+    // 按优先级从高到低排列，优先级相同时保持加入的顺序
+    sources: Vec<Arc<Dictionary>>,
+    disabled: RwLock<HashSet<String>>,
+}
+
+impl DictionarySet {
+    // sources 为 (词典, 优先级)，优先级高的词典的结果排在前面
+    pub fn new(sources: Vec<(Dictionary, i32)>) -> Self {
+        let mut sources = sources;
+        sources.sort_by_key(|(_, priority)| std::cmp::Reverse(*priority));
+        DictionarySet {
+            sources: sources.into_iter().map(|(dictionary, _)| Arc::new(dictionary)).collect(),
+            disabled: RwLock::new(HashSet::new()),
+        }
+    }
+
+    pub fn single(dictionary: Dictionary) -> Self {
+        Self::new(vec![(dictionary, 0)])
+    }
+
+    // 全部词典（包括被禁用的），按优先级排列
+    pub fn sources(&self) -> &[Arc<Dictionary>] {
+        &self.sources
+    }
+
+    pub fn source(&self, name: &str) -> Option<&Arc<Dictionary>> {
+        self.sources.iter().find(|dictionary| dictionary.name() == name)
+    }
+
+    // 参与查询的词典，按优先级排列
+    pub fn enabled_sources(&self) -> Vec<Arc<Dictionary>> {
+        let disabled = self.disabled.read().unwrap();
+        self.sources
+            .iter()
+            .filter(|dictionary| !disabled.contains(dictionary.name()))
+            .cloned()
+            .collect()
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        !self.disabled.read().unwrap().contains(name)
+    }
+
+    pub fn set_enabled(&self, name: &str, enabled: bool) {
+        let mut disabled = self.disabled.write().unwrap();
+        if enabled {
+            disabled.remove(name);
+        } else {
+            disabled.insert(name.to_string());
+        }
+    }
+
+    // 是否有多个词典（界面上只有这时才显示结果来源）
+    pub fn has_multiple_sources(&self) -> bool {
+        self.sources.len() > 1
+    }
+
+    // 任一词典的索引缺失或过期时返回 true
+    pub fn needs_index_build(&self) -> Result<bool, Box<dyn std::error::Error>> {
+        for dictionary in &self.sources {
+            if dictionary.needs_index_build()? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    // 依次全量重建每个词典的索引，返回合计的构建报告；取消时已重建的词典保留新索引，其余保持不变
+    pub async fn build_index_async(&self, control: BuildControl) -> Result<SyncSummary, Box<dyn std::error::Error>> {
+        let mut total = SyncSummary::default();
+        for dictionary in &self.sources {
+            match dictionary.build_index_async(control.clone()).await {
+                Ok(summary) => total.merge(summary),
+                Err(e) if e.is::<BuildCancelled>() => return Err(e),
+                Err(e) => return Err(format!("{}: {}", dictionary.name(), e).into()),
+            }
+        }
+        Ok(total)
+    }
+
+    pub async fn rebuild_index_async(&self) -> Result<SyncSummary, Box<dyn std::error::Error>> {
+        let mut total = SyncSummary::default();
+        for dictionary in &self.sources {
+            let summary = dictionary
+                .rebuild_index_async()
+                .await
+                .map_err(|e| format!("{}: {}", dictionary.name(), e))?;
+            total.merge(summary);
+        }
+        Ok(total)
+    }
+
+    // 依次增量同步每个词典的索引，返回合计的统计结果
+    pub async fn sync_index_async(&self) -> Result<SyncSummary, Box<dyn std::error::Error>> {
+        self.sync_index_with_control(BuildControl::default()).await
+    }
+
+    // 同 sync_index_async，通过 control 报告进度并可以取消
+    pub async fn sync_index_with_control(&self, control: BuildControl) -> Result<SyncSummary, Box<dyn std::error::Error>> {
+        let mut total = SyncSummary::default();
+        for dictionary in &self.sources {
+            match dictionary.sync_index_with_control(control.clone()).await {
+                Ok(summary) => total.merge(summary),
+                Err(e) if e.is::<BuildCancelled>() => return Err(e),
+                Err(e) => return Err(format!("{}: {}", dictionary.name(), e).into()),
+            }
+        }
+        Ok(total)
+    }
+
+    pub fn stats(&self) -> Result<Vec<IndexStats>, Box<dyn std::error::Error>> {
+        self.sources.iter().map(|dictionary| dictionary.stats()).collect()
+    }
+
+    // 查找单词：优先取精确匹配词头的结果，其次是通过屈折形式找到的原形，同类结果按词典优先级
+    pub fn lookup(&self, word: &str) -> Result<Option<LookupResult>, Box<dyn std::error::Error>> {
+        Ok(self.lookup_all(word)?.into_iter().next())
+    }
+
+    pub fn lookup_word(&self, word: &str) -> Result<Option<WordDefinition>, Box<dyn std::error::Error>> {
+        Ok(self.lookup(word)?.map(|result| result.definition))
+    }
+
+    // 各词典中的全部匹配，排序规则同 lookup
+    pub fn lookup_all(&self, word: &str) -> Result<Vec<LookupResult>, Box<dyn std::error::Error>> {
+        let mut results = Vec::new();
+        for dictionary in self.enabled_sources() {
+            if let Some(result) = dictionary.lookup(word)? {
+                results.push(result);
+            }
+        }
+        results.sort_by_key(|result| result.inflection.is_some());
+        Ok(results)
+    }
+
+    // 自动完成：合并各词典的词头，按字母顺序去重
+    pub fn complete_prefix(&self, prefix: &str, limit: usize) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let mut words = Vec::new();
+        for dictionary in self.enabled_sources() {
+            words.extend(dictionary.complete_prefix(prefix, limit)?);
+        }
+        Ok(merge_headwords(words, limit))
+    }
+
+    // 前缀搜索：同一词头取优先级最高的词典中的简明释义
+    pub fn search_words(&self, prefix: &str, limit: usize) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+        let mut results: Vec<(String, String)> = Vec::new();
+        let mut seen = HashSet::new();
+        for dictionary in self.enabled_sources() {
+            for (word, concise) in dictionary.search_words(prefix, limit)? {
+                if seen.insert(normalize_headword(&word)) {
+                    results.push((word, concise));
+                }
+            }
+        }
+        results.sort_by_cached_key(|(word, _)| (normalize_headword(word), word.clone()));
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    // 汉译英：按词典优先级依次列出，同一词头只保留一次
+    pub fn reverse_search(&self, query: &str, limit: usize) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+        let mut results: Vec<(String, String)> = Vec::new();
+        let mut seen = HashSet::new();
+        for dictionary in self.enabled_sources() {
+            for (word, concise) in dictionary.reverse_search(query, limit)? {
+                if seen.insert(normalize_headword(&word)) {
+                    results.push((word, concise));
+                }
+            }
+        }
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    // 全文检索：合并各词典的命中并按得分排序，得分相同时按词典优先级
+    pub fn full_text_search(&self, query: &str, limit: usize) -> Result<Vec<FullTextHit>, Box<dyn std::error::Error>> {
+        let mut hits = Vec::new();
+        for dictionary in self.enabled_sources() {
+            hits.extend(dictionary.full_text_search(query, limit)?);
+        }
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits.truncate(limit);
+        Ok(hits)
+    }
+
+    // 拼写纠错：合并各词典的候选，按编辑距离排序，距离相同时按词典优先级
+    pub fn suggest_similar(&self, word: &str, limit: usize) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let query_word = normalize_headword(word);
+        let mut candidates = Vec::new();
+        for dictionary in self.enabled_sources() {
+            candidates.extend(dictionary.suggest_similar(word, limit)?);
+        }
+        candidates.sort_by_cached_key(|candidate| edit_distance(&query_word, &normalize_headword(candidate)));
+
+        let mut seen = HashSet::new();
+        candidates.retain(|candidate| seen.insert(normalize_headword(candidate)));
+        candidates.truncate(limit);
+        Ok(candidates)
+    }
+}
+
+// 合并多个有序词头列表：按规范化形式排序去重，取前 limit 个
+fn merge_headwords(mut words: Vec<String>, limit: usize) -> Vec<String> {
+    words.sort_by_cached_key(|word| (normalize_headword(word), word.clone()));
+    words.dedup_by(|a, b| normalize_headword(a) == normalize_headword(b));
+    words.truncate(limit);
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(temp: &tempfile::TempDir, name: &str, entries: &[serde_json::Value]) -> Dictionary {
+        let words_dir = temp.path().join(name).join("words");
+        std::fs::create_dir_all(&words_dir).unwrap();
+        for entry in entries {
+            let file_name = format!("{}.json", entry["word"].as_str().unwrap());
+            std::fs::write(words_dir.join(file_name), entry.to_string()).unwrap();
+        }
+        Dictionary::with_index_path(
+            words_dir.to_string_lossy().into_owned(),
+            temp.path().join(name).join("index").to_string_lossy().into_owned(),
+        )
+        .with_name(name)
+    }
+
+    // 通用词典优先级低，先加入；领域词典优先级高，其中的 give 带有屈折形式 gave，通用词典里 gave 是独立的词头
+    fn two_sources(temp: &tempfile::TempDir) -> DictionarySet {
+        let general = source(
+            temp,
+            "general",
+            &[
+                serde_json::json!({"word": "give", "concise_definition": "v. 给"}),
+                serde_json::json!({"word": "gave", "concise_definition": "give 的过去式"}),
+            ],
+        );
+        let domain = source(
+            temp,
+            "domain",
+            &[serde_json::json!({
+                "word": "give",
+                "concise_definition": "n. 弹性",
+                "forms": {"past_tense": "gave"}
+            })],
+        );
+        let set = DictionarySet::new(vec![(general, 0), (domain, 10)]);
+        tokio::runtime::Runtime::new().unwrap().block_on(set.rebuild_index_async()).unwrap();
+        set
+    }
+
+    fn sources_of(results: &[LookupResult]) -> Vec<(&str, &str, bool)> {
+        results
+            .iter()
+            .map(|result| (result.source.as_str(), result.definition.word.as_str(), result.inflection.is_some()))
+            .collect()
+    }
+
+    #[test]
+    fn sources_are_ordered_by_priority() {
+        let temp = tempfile::tempdir().unwrap();
+        let set = two_sources(&temp);
+        let names: Vec<&str> = set.sources().iter().map(|dictionary| dictionary.name()).collect();
+        assert_eq!(names, ["domain", "general"]);
+        assert!(set.has_multiple_sources());
+
+        let results = set.lookup_all("give").unwrap();
+        assert_eq!(sources_of(&results), [("domain", "give", false), ("general", "give", false)]);
+        assert_eq!(set.lookup("give").unwrap().unwrap().definition.concise_definition.as_deref(), Some("n. 弹性"));
+    }
+
+    #[test]
+    fn exact_headwords_come_before_inflections_from_higher_priority_sources() {
+        let temp = tempfile::tempdir().unwrap();
+        let set = two_sources(&temp);
+        let results = set.lookup_all("gave").unwrap();
+        assert_eq!(sources_of(&results), [("general", "gave", false), ("domain", "give", true)]);
+    }
+
+    #[test]
+    fn disabled_sources_are_left_out_of_queries() {
+        let temp = tempfile::tempdir().unwrap();
+        let set = two_sources(&temp);
+
+        set.set_enabled("domain", false);
+        assert!(!set.is_enabled("domain"));
+        let names: Vec<String> = set.enabled_sources().iter().map(|dictionary| dictionary.name().to_string()).collect();
+        assert_eq!(names, ["general"]);
+        assert_eq!(sources_of(&set.lookup_all("give").unwrap()), [("general", "give", false)]);
+        assert_eq!(set.lookup("give").unwrap().unwrap().definition.concise_definition.as_deref(), Some("v. 给"));
+        // 被禁用的词典仍然在 sources 中
+        assert_eq!(set.sources().len(), 2);
+
+        set.set_enabled("domain", true);
+        assert!(set.is_enabled("domain"));
+        assert_eq!(set.lookup("give").unwrap().unwrap().source, "domain");
+    }
+
+    #[test]
+    fn prefix_results_are_merged_across_sources() {
+        let temp = tempfile::tempdir().unwrap();
+        let set = two_sources(&temp);
+        assert_eq!(set.complete_prefix("g", 10).unwrap(), ["gave", "give"]);
+        let words = set.search_words("gi", 10).unwrap();
+        assert_eq!(words, [("give".to_string(), "n. 弹性".to_string())]);
+    }
+}
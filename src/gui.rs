@@ -1,17 +1,60 @@
-use crate::dictionary::Dictionary;
-use crate::models::WordDefinition;
+use crate::cjk;
+use crate::dictionary::{
+    BuildCancelled, BuildControl, BuildIssue, BuildIssueKind, BuildProgress, Dictionary, FullTextHit, LookupResult,
+    SyncSummary,
+};
+use crate::dictionary_set::DictionarySet;
+use crate::models::{Inflection, WordDefinition};
+use crate::pack;
+use crate::watcher::WordsWatcher;
 use eframe::egui;
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
+use tokio_util::sync::CancellationToken;
+
+// Lines shown before and after the failing line in the build report
+const EXCERPT_CONTEXT_LINES: usize = 3;
+// Pack lines can hold a whole entry; cut them in the excerpt
+const EXCERPT_MAX_CHARS: usize = 200;
+
+// User preferences persisted through eframe storage
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AppSettings {
+    // Check the index at launch and sync it in the background when missing or stale
+    pub auto_build_index: bool,
+    // Dictionaries enabled or disabled in the settings menu, by name; overrides the config file
+    pub dictionary_enabled: BTreeMap<String, bool>,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            auto_build_index: true,
+            dictionary_enabled: BTreeMap::new(),
+        }
+    }
+}
 
 pub struct DictNaviApp {
-    dictionary: Arc<Dictionary>,
+    dictionary: Arc<DictionarySet>,
     search_term: String,
     search_result: Option<WordDefinition>,
+    search_inflection: Option<Inflection>, // Set when the search term is an inflected form of the result
+    search_source: Option<String>, // Dictionary the result came from
+    other_results: Vec<LookupResult>, // Entries for the same word in lower-priority dictionaries
     error_message: Option<String>,
+    did_you_mean: Vec<String>, // Closest headwords when a lookup finds nothing
+    reverse_results: Vec<(String, String)>, // Chinese-to-English lookup results: (word, concise definition)
+    full_text_mode: bool, // Search explanations and examples instead of headwords
+    full_text_results: Vec<FullTextHit>,
     search_history: Vec<String>,
     // Fields related to autocomplete
-    all_words: Option<Vec<String>>,
     suggestions: Vec<String>,
     selected_index: Option<usize>,
     show_suggestions: bool,
@@ -20,18 +63,60 @@ pub struct DictNaviApp {
     show_settings_menu: bool,
     sync_status: Option<String>, // Status message for index building
     is_building_index: Arc<Mutex<bool>>, // Whether index is being built
+    build_progress: Arc<Mutex<BuildProgress>>, // Files processed so far by the running build
+    build_cancel: Option<CancellationToken>, // Cancels the running build
     build_result: Arc<Mutex<Option<String>>>, // Result of index building
+    build_report_result: Arc<Mutex<Option<SyncSummary>>>, // Report of the last finished build, picked up by the UI thread
+    build_report: Option<SyncSummary>, // Report shown in the build report window
+    show_build_report: bool,
+    selected_issue: Option<usize>, // Issue clicked in the build report
+    issue_excerpt: Vec<(usize, String)>, // Lines of the selected issue's file around the error: (line number, text)
+    settings: AppSettings,
+    // Fields related to live reindexing of the words directory
+    _words_watchers: Vec<WordsWatcher>, // One per dictionary
+    watcher_changes: Arc<Mutex<Vec<String>>>, // Headwords reindexed by the watcher since the last frame
 }
 
 impl DictNaviApp {
-    pub fn new(dictionary: Dictionary) -> Self {
-        Self {
-            dictionary: Arc::new(dictionary),
+    pub fn new(cc: &eframe::CreationContext<'_>, dictionary: DictionarySet) -> Self {
+        let settings: AppSettings = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, eframe::APP_KEY))
+            .unwrap_or_default();
+
+        for (name, enabled) in &settings.dictionary_enabled {
+            dictionary.set_enabled(name, *enabled);
+        }
+
+        let dictionary = Arc::new(dictionary);
+        let build_result = Arc::new(Mutex::new(None));
+        let watcher_changes = Arc::new(Mutex::new(Vec::new()));
+        let words_watchers = dictionary
+            .sources()
+            .iter()
+            .filter_map(|source| {
+                Self::start_words_watcher(
+                    &cc.egui_ctx,
+                    Arc::clone(source),
+                    Arc::clone(&watcher_changes),
+                    Arc::clone(&build_result),
+                )
+            })
+            .collect();
+
+        let mut app = Self {
+            dictionary,
             search_term: String::new(),
             search_result: None,
+            search_inflection: None,
+            search_source: None,
+            other_results: Vec::new(),
             error_message: None,
+            did_you_mean: Vec::new(),
+            reverse_results: Vec::new(),
+            full_text_mode: false,
+            full_text_results: Vec::new(),
             search_history: Vec::new(),
-            all_words: None,
             suggestions: Vec::new(),
             selected_index: None,
             show_suggestions: false,
@@ -39,48 +124,69 @@ impl DictNaviApp {
             show_settings_menu: false,
             sync_status: None,
             is_building_index: Arc::new(Mutex::new(false)),
-            build_result: Arc::new(Mutex::new(None)),
-        }
-    }
+            build_progress: Arc::new(Mutex::new(BuildProgress::default())),
+            build_cancel: None,
+            build_result,
+            build_report_result: Arc::new(Mutex::new(None)),
+            build_report: None,
+            show_build_report: false,
+            selected_issue: None,
+            issue_excerpt: Vec::new(),
+            settings,
+            _words_watchers: words_watchers,
+            watcher_changes,
+        };
 
-    // Load all words list (lazy loading)
-    fn load_all_words(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        if self.all_words.is_none() {
-            self.all_words = Some(self.dictionary.list_words()?);
+        if app.settings.auto_build_index {
+            app.start_startup_index_check();
         }
-        Ok(())
+
+        app
     }
 
     // Filter words based on input
     fn update_suggestions(&mut self) {
         if self.search_term.is_empty() {
+            self.suggestions.clear();
+            self.show_suggestions = false;
+            self.selected_index = None;
+            self.reverse_results.clear();
+            return;
+        }
+
+        // Prefix suggestions do not apply to full-text queries
+        if self.full_text_mode {
             self.suggestions.clear();
             self.show_suggestions = false;
             self.selected_index = None;
             return;
         }
 
-        if let Err(_) = self.load_all_words() {
+        // Chinese input switches to reverse lookup: results are listed in the main area
+        if cjk::contains_cjk(&self.search_term) {
             self.suggestions.clear();
             self.show_suggestions = false;
+            self.selected_index = None;
+            self.reverse_lookup();
             return;
         }
+        self.reverse_results.clear();
 
-        let search_lower = self.search_term.to_lowercase();
-        if let Some(ref words) = self.all_words {
-            self.suggestions = words
-                .iter()
-                .filter(|word| word.to_lowercase().starts_with(&search_lower))
-                .take(10) // Display up to 10 suggestions
-                .cloned()
-                .collect();
-            
-            self.show_suggestions = !self.suggestions.is_empty();
-            // Reset selected index
-            if self.selected_index.is_some() && self.selected_index.unwrap() >= self.suggestions.len() {
-                self.selected_index = None;
+        // Display up to 10 suggestions
+        match self.dictionary.complete_prefix(&self.search_term, 10) {
+            Ok(words) => self.suggestions = words,
+            Err(_) => {
+                self.suggestions.clear();
+                self.show_suggestions = false;
+                return;
             }
         }
+
+        self.show_suggestions = !self.suggestions.is_empty();
+        // Reset selected index
+        if self.selected_index.is_some_and(|idx| idx >= self.suggestions.len()) {
+            self.selected_index = None;
+        }
     }
 
     fn search_word(&mut self) {
@@ -88,26 +194,406 @@ impl DictNaviApp {
             return;
         }
 
-        match self.dictionary.lookup_word(&self.search_term) {
-            Ok(Some(definition)) => {
-                self.search_result = Some(definition);
+        if cjk::contains_cjk(&self.search_term) {
+            self.reverse_lookup();
+            return;
+        }
+
+        if self.full_text_mode {
+            self.full_text_lookup();
+            return;
+        }
+
+        self.lookup_search_term();
+    }
+
+    // Open the entry for `word` directly, regardless of the current search mode
+    fn open_entry(&mut self, word: String) {
+        self.search_term = word;
+        self.show_suggestions = false;
+        self.selected_index = None;
+        self.lookup_search_term();
+    }
+
+    fn lookup_search_term(&mut self) {
+        self.reverse_results.clear();
+        self.did_you_mean.clear();
+        match self.dictionary.lookup_all(&self.search_term) {
+            Ok(results) if !results.is_empty() => {
+                self.set_results(results);
                 self.error_message = None;
             }
-            Ok(None) => {
-                self.search_result = None;
+            Ok(_) => {
+                self.set_results(Vec::new());
                 self.error_message = Some(format!("Word '{}' not found", self.search_term));
+                self.did_you_mean = self
+                    .dictionary
+                    .suggest_similar(&self.search_term, 5)
+                    .unwrap_or_default();
             }
             Err(e) => {
+                self.set_results(Vec::new());
+                self.error_message = Some(format!("Error looking up word: {}", e));
+            }
+        }
+    }
+
+    // Show the best match, with the entries from other dictionaries below it
+    fn set_results(&mut self, results: Vec<LookupResult>) {
+        let mut results = results.into_iter();
+        match results.next() {
+            Some(best) => {
+                self.search_result = Some(best.definition);
+                self.search_inflection = best.inflection;
+                self.search_source = Some(best.source);
+            }
+            None => {
                 self.search_result = None;
+                self.search_inflection = None;
+                self.search_source = None;
+            }
+        }
+        self.other_results = results.collect();
+    }
+
+    // Search explanations, examples and comparisons for the search term
+    fn full_text_lookup(&mut self) {
+        self.set_results(Vec::new());
+        self.did_you_mean.clear();
+
+        match self.dictionary.full_text_search(&self.search_term, 50) {
+            Ok(hits) if hits.is_empty() => {
+                self.full_text_results.clear();
+                self.error_message = Some(format!("No entries mention '{}'", self.search_term));
+            }
+            Ok(hits) => {
+                self.full_text_results = hits;
+                self.error_message = None;
+            }
+            Err(e) => {
+                self.full_text_results.clear();
+                self.error_message = Some(format!("Error searching text: {}", e));
+            }
+        }
+    }
+
+    // Find English headwords whose Chinese glosses match the search term
+    fn reverse_lookup(&mut self) {
+        self.set_results(Vec::new());
+        self.did_you_mean.clear();
+
+        match self.dictionary.reverse_search(&self.search_term, 50) {
+            Ok(results) if results.is_empty() => {
+                self.reverse_results.clear();
+                self.error_message = Some(format!("No English words found for '{}'", self.search_term));
+            }
+            Ok(results) => {
+                self.reverse_results = results;
+                self.error_message = None;
+            }
+            Err(e) => {
+                self.reverse_results.clear();
+                self.error_message = Some(format!("Error looking up word: {}", e));
+            }
+        }
+    }
+
+    // Control for a new build: progress goes to `build_progress`, the token is kept for the Cancel button
+    fn new_build_control(&mut self) -> BuildControl {
+        *self.build_progress.lock().unwrap() = BuildProgress::default();
+        let progress_arc = Arc::clone(&self.build_progress);
+        let control = BuildControl {
+            progress: Some(Arc::new(move |progress| *progress_arc.lock().unwrap() = progress)),
+            cancel: CancellationToken::new(),
+        };
+        self.build_cancel = Some(control.cancel.clone());
+        control
+    }
+
+    // Progress bar and Cancel button for the running build
+    fn show_build_progress(&mut self, ui: &mut egui::Ui) {
+        let progress = *self.build_progress.lock().unwrap();
+        let cancelling = self.build_cancel.as_ref().is_some_and(|cancel| cancel.is_cancelled());
+        ui.horizontal(|ui| {
+            // The number of entries in a pack is not known in advance
+            let bar = if progress.total > 0 {
+                egui::ProgressBar::new(progress.processed as f32 / progress.total as f32)
+                    .text(format!("{} / {} files", progress.processed, progress.total))
+            } else {
+                egui::ProgressBar::new(0.0)
+                    .animate(true)
+                    .text(format!("{} entries", progress.processed))
+            };
+            ui.add(bar.desired_width(180.0));
+
+            let cancel_text = if cancelling { "Cancelling..." } else { "Cancel" };
+            if ui.add_enabled(!cancelling, egui::Button::new(cancel_text)).clicked()
+                && let Some(cancel) = &self.build_cancel
+            {
+                cancel.cancel();
+            }
+        });
+    }
+
+    // Start an asynchronous index sync, or a full rebuild when `full_rebuild` is set
+    fn start_index_build(&mut self, full_rebuild: bool) {
+        *self.is_building_index.lock().unwrap() = true;
+        let control = self.new_build_control();
+        self.sync_status = Some(if full_rebuild {
+            "Rebuilding index, please wait...".to_string()
+        } else {
+            "Syncing index, please wait...".to_string()
+        });
+
+        let dictionary = Arc::clone(&self.dictionary);
+        let status_arc = Arc::clone(&self.is_building_index);
+        let result_arc = Arc::clone(&self.build_result);
+        let report_arc = Arc::clone(&self.build_report_result);
+
+        tokio::spawn(async move {
+            let message = if full_rebuild {
+                match dictionary.build_index_async(control).await {
+                    Ok(summary) => {
+                        let message = format!(
+                            "Index built successfully! Indexed {} documents (total {} files){}",
+                            summary.added,
+                            summary.total_files,
+                            report_hint(&summary)
+                        );
+                        *report_arc.lock().unwrap() = Some(summary);
+                        message
+                    }
+                    Err(e) if e.is::<BuildCancelled>() => CANCELLED_MESSAGE.to_string(),
+                    Err(e) => format!("Index building failed: {}", e),
+                }
+            } else {
+                match dictionary.sync_index_with_control(control).await {
+                    Ok(summary) => {
+                        let message = sync_message(&summary);
+                        *report_arc.lock().unwrap() = Some(summary);
+                        message
+                    }
+                    Err(e) if e.is::<BuildCancelled>() => CANCELLED_MESSAGE.to_string(),
+                    Err(e) => format!("Index sync failed: {}", e),
+                }
+            };
+
+            *status_arc.lock().unwrap() = false;
+            *result_arc.lock().unwrap() = Some(message);
+        });
+    }
+
+    // Watch the words directory and reindex edited files as they change on disk
+    fn start_words_watcher(
+        ctx: &egui::Context,
+        dictionary: Arc<Dictionary>,
+        changes_arc: Arc<Mutex<Vec<String>>>,
+        result_arc: Arc<Mutex<Option<String>>>,
+    ) -> Option<WordsWatcher> {
+        let ctx = ctx.clone();
+        let watcher = WordsWatcher::start(dictionary, move |result| {
+            match result {
+                Ok(summary) => changes_arc.lock().unwrap().extend(summary.changed_words),
+                Err(e) => *result_arc.lock().unwrap() = Some(format!("Live reindex failed: {}", e)),
+            }
+            ctx.request_repaint();
+        });
+
+        match watcher {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                eprintln!("Warning: Failed to watch words directory: {}", e);
+                None
+            }
+        }
+    }
+
+    // Apply changes reported by the watcher: refresh suggestions and the displayed entry
+    fn apply_watcher_changes(&mut self) {
+        let changed: Vec<String> = std::mem::take(&mut *self.watcher_changes.lock().unwrap());
+        if changed.is_empty() {
+            return;
+        }
+
+        if !self.search_term.is_empty() {
+            self.update_suggestions();
+        }
+
+        let Some(current) = &self.search_result else {
+            return;
+        };
+        if !changed.iter().any(|word| word.eq_ignore_ascii_case(&current.word)) {
+            return;
+        }
+
+        let word = current.word.clone();
+        let inflection = self.search_inflection.take();
+        match self.dictionary.lookup_all(&word) {
+            Ok(results) if !results.is_empty() => {
+                self.set_results(results);
+                self.search_inflection = inflection;
+            }
+            Ok(_) => {
+                self.set_results(Vec::new());
+                self.error_message = Some(format!("Word '{}' not found", word));
+            }
+            Err(e) => {
+                self.set_results(Vec::new());
                 self.error_message = Some(format!("Error looking up word: {}", e));
             }
         }
     }
 
+    // Check index staleness in the background and sync it if it is missing or out of date
+    fn start_startup_index_check(&mut self) {
+        *self.is_building_index.lock().unwrap() = true;
+        let control = self.new_build_control();
+
+        let dictionary = Arc::clone(&self.dictionary);
+        let status_arc = Arc::clone(&self.is_building_index);
+        let result_arc = Arc::clone(&self.build_result);
+        let report_arc = Arc::clone(&self.build_report_result);
+
+        tokio::spawn(async move {
+            let check_dictionary = Arc::clone(&dictionary);
+            let needs_build = tokio::task::spawn_blocking(move || {
+                check_dictionary
+                    .needs_index_build()
+                    .map_err(|e| e.to_string())
+            })
+            .await;
+
+            let message = match needs_build {
+                Ok(Ok(true)) => {
+                    *result_arc.lock().unwrap() = Some(
+                        "Index is missing or out of date, building it in the background...".to_string(),
+                    );
+                    match dictionary.sync_index_with_control(control).await {
+                        Ok(summary) => {
+                            let message = sync_message(&summary);
+                            *report_arc.lock().unwrap() = Some(summary);
+                            Some(message)
+                        }
+                        Err(e) if e.is::<BuildCancelled>() => Some(CANCELLED_MESSAGE.to_string()),
+                        Err(e) => Some(format!("Index sync failed: {}", e)),
+                    }
+                }
+                Ok(Ok(false)) => None,
+                Ok(Err(e)) => Some(format!("Index check failed: {}", e)),
+                Err(e) => Some(format!("Index check failed: {}", e)),
+            };
+
+            *status_arc.lock().unwrap() = false;
+            if let Some(message) = message {
+                *result_arc.lock().unwrap() = Some(message);
+            }
+        });
+    }
+
+    // Select an issue in the build report and load the lines of its file around the error
+    fn select_issue(&mut self, index: usize) {
+        self.selected_issue = Some(index);
+        self.issue_excerpt = self
+            .build_report
+            .as_ref()
+            .and_then(|report| report.issues.get(index))
+            .map(read_excerpt)
+            .unwrap_or_default();
+    }
+
+    // Window listing the problems found by the last build; clicking a file shows its error and the lines around it
+    fn show_build_report_window(&mut self, ctx: &egui::Context) {
+        let Some(report) = &self.build_report else {
+            return;
+        };
+
+        let mut open = self.show_build_report;
+        let mut clicked_issue = None;
+        egui::Window::new("Build report")
+            .open(&mut open)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{}: {} added, {} updated, {} removed, {} unchanged in {:.1}s",
+                    if report.rebuilt { "Rebuilt" } else { "Synced" },
+                    report.added,
+                    report.updated,
+                    report.removed,
+                    report.unchanged,
+                    report.elapsed_ms as f64 / 1000.0
+                ));
+                let duplicates = report
+                    .issues
+                    .iter()
+                    .filter(|issue| issue.kind == BuildIssueKind::DuplicateHeadword)
+                    .count();
+                ui.label(format!(
+                    "{} files, {} skipped, {} duplicate headwords",
+                    report.total_files,
+                    report.skipped_files.len(),
+                    duplicates
+                ));
+                ui.separator();
+
+                if report.issues.is_empty() {
+                    ui.colored_label(egui::Color32::from_rgb(0, 150, 0), "No problems found.");
+                    return;
+                }
+
+                egui::ScrollArea::vertical()
+                    .id_source("build_report_issues")
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        for (index, issue) in report.issues.iter().enumerate() {
+                            let text = format!("[{}] {}", issue.kind.label(), issue.location());
+                            if ui.selectable_label(self.selected_issue == Some(index), text).clicked() {
+                                clicked_issue = Some(index);
+                            }
+                        }
+                    });
+
+                let Some(issue) = self.selected_issue.and_then(|index| report.issues.get(index)) else {
+                    ui.label(egui::RichText::new("Click a file to see the error.").weak());
+                    return;
+                };
+                ui.separator();
+                ui.label(egui::RichText::new(issue.location()).strong());
+                ui.colored_label(egui::Color32::RED, &issue.message);
+
+                if !self.issue_excerpt.is_empty() {
+                    let mut excerpt = String::new();
+                    for (number, text) in &self.issue_excerpt {
+                        let marker = if Some(*number) == issue.line { ">" } else { " " };
+                        excerpt.push_str(&format!("{} {:>5} | {}\n", marker, number, text));
+                        // Caret under the column of the error
+                        if Some(*number) == issue.line
+                            && let Some(column) = issue.column
+                        {
+                            excerpt.push_str(&format!("{:>9}{}^\n", "", " ".repeat(column.saturating_sub(1))));
+                        }
+                    }
+                    egui::ScrollArea::both()
+                        .id_source("build_report_excerpt")
+                        .max_height(240.0)
+                        .show(ui, |ui| {
+                            ui.label(egui::RichText::new(excerpt).monospace());
+                        });
+                }
+            });
+
+        self.show_build_report = open;
+        if let Some(index) = clicked_issue {
+            self.select_issue(index);
+        }
+    }
+
     fn clear_search(&mut self) {
         self.search_term.clear();
-        self.search_result = None;
+        self.set_results(Vec::new());
         self.error_message = None;
+        self.did_you_mean.clear();
+        self.reverse_results.clear();
+        self.full_text_results.clear();
         self.show_suggestions = false;
         self.selected_index = None;
         self.suggestions.clear();
@@ -116,15 +602,25 @@ impl DictNaviApp {
 }
 
 impl eframe::App for DictNaviApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, &self.settings);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Check the result of asynchronous index building
         if let Ok(mut result) = self.build_result.lock() {
             if let Some(status) = result.take() {
                 self.sync_status = Some(status);
-                // Clear the cached word list, force reload
-                self.all_words = None;
             }
         }
+        if let Some(report) = self.build_report_result.lock().unwrap().take() {
+            self.build_report = Some(report);
+            self.selected_issue = None;
+            self.issue_excerpt.clear();
+        }
+
+        // Pick up entries reindexed by the words directory watcher
+        self.apply_watcher_changes();
         
         // If index is being built, request repaint periodically to update UI
         if *self.is_building_index.lock().unwrap() {
@@ -163,41 +659,58 @@ impl eframe::App for DictNaviApp {
                             .show(ui, |ui| {
                                 ui.set_min_width(150.0);
                                 
-                                // Build index option (asynchronous)
+                                // Index options (asynchronous)
                                 let is_building = *self.is_building_index.lock().unwrap();
-                                let button_text = if is_building {
-                                    "Building index..."
+                                let sync_text = if is_building {
+                                    "Syncing index..."
                                 } else {
-                                    "Build index"
+                                    "Sync index"
                                 };
-                                
-                                let button = ui.add_enabled(!is_building, egui::Button::new(button_text));
-                                if button.clicked() && !is_building {
+
+                                let sync_button = ui.add_enabled(!is_building, egui::Button::new(sync_text));
+                                if sync_button.clicked() && !is_building {
                                     self.show_settings_menu = false;
-                                    *self.is_building_index.lock().unwrap() = true;
-                                    self.sync_status = Some("Building index, please wait...".to_string());
-                                    
-                                    let dictionary = Arc::clone(&self.dictionary);
-                                    let status_arc = Arc::clone(&self.is_building_index);
-                                    let result_arc = Arc::clone(&self.build_result);
-                                    
-                                    tokio::spawn(async move {
-                                        let result = dictionary.build_index_async().await;
-                                        
-                                        *status_arc.lock().unwrap() = false;
-                                        
-                                        match result {
-                                            Ok((doc_count, json_count)) => {
-                                                *result_arc.lock().unwrap() = Some(format!(
-                                                    "Index built successfully! Indexed {} documents (total {} files)",
-                                                    doc_count, json_count
-                                                ));
-                                            }
-                                            Err(e) => {
-                                                *result_arc.lock().unwrap() = Some(format!("Index building failed: {}", e));
-                                            }
+                                    self.start_index_build(false);
+                                }
+
+                                let rebuild_button = ui.add_enabled(!is_building, egui::Button::new("Rebuild index"));
+                                if rebuild_button.clicked() && !is_building {
+                                    self.show_settings_menu = false;
+                                    self.start_index_build(true);
+                                }
+
+                                if is_building {
+                                    self.show_build_progress(ui);
+                                }
+
+                                let report_button = ui.add_enabled(self.build_report.is_some(), egui::Button::new("Build report"));
+                                if report_button.clicked() {
+                                    self.show_settings_menu = false;
+                                    self.show_build_report = true;
+                                }
+
+                                ui.separator();
+                                ui.checkbox(&mut self.settings.auto_build_index, "Build index on startup");
+
+                                // Per-dictionary toggles when several dictionaries are loaded
+                                if self.dictionary.has_multiple_sources() {
+                                    ui.separator();
+                                    ui.label("Dictionaries");
+                                    let mut toggled = false;
+                                    for source in self.dictionary.sources() {
+                                        let mut enabled = self.dictionary.is_enabled(source.name());
+                                        if ui.checkbox(&mut enabled, source.name()).changed() {
+                                            self.dictionary.set_enabled(source.name(), enabled);
+                                            self.settings.dictionary_enabled.insert(source.name().to_string(), enabled);
+                                            toggled = true;
                                         }
-                                    });
+                                    }
+                                    if toggled {
+                                        self.update_suggestions();
+                                        if self.search_result.is_some() || self.error_message.is_some() {
+                                            self.search_word();
+                                        }
+                                    }
                                 }
                             });
                     });
@@ -218,13 +731,25 @@ impl eframe::App for DictNaviApp {
             // Display sync status message
             if let Some(status) = &self.sync_status {
                 let is_success = status.contains("successfully");
+                let is_building = *self.is_building_index.lock().unwrap();
                 let status_clone = status.clone();
                 ui.horizontal(|ui| {
-                    if is_success {
+                    if is_building {
+                        // Banner shown while an index build is running
+                        ui.spinner();
+                        ui.label(&status_clone);
+                        self.show_build_progress(ui);
+                    } else if is_success {
                         ui.colored_label(egui::Color32::from_rgb(0, 150, 0), &status_clone);
                     } else {
                         ui.colored_label(egui::Color32::RED, &status_clone);
                     }
+                    if !is_building
+                        && self.build_report.as_ref().is_some_and(|report| !report.issues.is_empty())
+                        && ui.button("Details").clicked()
+                    {
+                        self.show_build_report = true;
+                    }
                     if ui.button("✕").clicked() {
                         self.sync_status = None;
                     }
@@ -400,6 +925,14 @@ impl eframe::App for DictNaviApp {
                     self.search_word();
                 }
 
+                if ui
+                    .toggle_value(&mut self.full_text_mode, "Full text")
+                    .on_hover_text("Search explanations, examples and comparisons")
+                    .changed()
+                {
+                    self.update_suggestions();
+                }
+
                 if ui.button("Clear").clicked() {
                     self.clear_search();
                 }
@@ -415,8 +948,7 @@ impl eframe::App for DictNaviApp {
                         self.search_history.iter().rev().take(10).cloned().collect();
                     for word in recent_words {
                         if ui.button(&word).clicked() {
-                            self.search_term = word.clone();
-                            self.search_word();
+                            self.open_entry(word.clone());
                         }
                     }
                 });
@@ -425,94 +957,262 @@ impl eframe::App for DictNaviApp {
             ui.separator();
 
             // Display results or errors in a scrollable area
+            let mut clicked_entry = None;
             egui::ScrollArea::vertical().show(ui, |ui| {
                 if let Some(error) = &self.error_message {
                     ui.colored_label(egui::Color32::RED, error);
+
+                    // Spelling suggestions for a word that was not found
+                    if !self.did_you_mean.is_empty() {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label("Did you mean:");
+                            for suggestion in &self.did_you_mean {
+                                if ui.link(suggestion).clicked() {
+                                    clicked_entry = Some(suggestion.clone());
+                                }
+                            }
+                        });
+                    }
                 } else if let Some(definition) = &self.search_result {
                     // Add search result to search history
                     if !self.search_history.contains(&definition.word) {
                         self.search_history.push(definition.word.clone());
                     }
 
-                    // Display word information
-                    ui.heading(&definition.word);
-                    if let Some(phonetic) = &definition.phonetic {
-                        if !phonetic.is_empty() {
-                            ui.label(format!("/{}/", phonetic));
-                        }
+                    // Show how the search term relates to the entry, e.g. "abandoned → past tense of abandon"
+                    if let Some(inflection) = &self.search_inflection {
+                        ui.label(egui::RichText::new(inflection.describe()).italics().weak());
                     }
 
-                    // Display concise definition if available with better styling
-                    if let Some(concise_def) = &definition.concise_definition {
-                        ui.horizontal(|ui| {
-                            ui.colored_label(egui::Color32::from_rgb(0, 100, 0), concise_def);
-                        });
+                    // Show which dictionary the entry came from when several are loaded
+                    if self.dictionary.has_multiple_sources()
+                        && let Some(source) = &self.search_source
+                    {
+                        ui.label(egui::RichText::new(source).small().weak());
                     }
 
-                    ui.separator();
-
-                    // Display meanings
-                    if let Some(meanings) = &definition.meanings {
-                        for (i, meaning) in meanings.iter().enumerate() {
-                            // Part of speech with color
-                            ui.colored_label(
-                                egui::Color32::DARK_BLUE,
-                                format!("{}. {}", i + 1, meaning.part_of_speech),
-                            );
-
-                            // English explanation
-                            ui.label(format!("{}", meaning.explanation_en));
-                            // Transaltion explanation with color
-                            if let Some(explanation_cn) = &meaning.explanation_cn {
-                                ui.colored_label(
-                                    egui::Color32::from_rgb(0, 100, 0),
-                                    explanation_cn,
-                                );
-                            }
+                    show_definition(ui, definition);
 
-                            // English example with italic style
-                            if let Some(example) = &meaning.example_en {
-                                ui.horizontal(|ui| {
-                                    ui.add_space(10.0);
-                                    ui.label(egui::RichText::new(example).italics());
-                                });
-                            }
-
-                            // Chinese example with color and italic style
-                            if let Some(example_cn) = &meaning.example_cn {
-                                ui.horizontal(|ui| {
-                                    ui.add_space(10.0);
-                                    ui.label(
-                                        egui::RichText::new(example_cn)
-                                            .color(egui::Color32::from_rgb(0, 100, 0)).
-                                            italics()
-                                    );
-                                });
-                            }
-
-                            ui.add_space(10.0);
+                    // The same word in lower-priority dictionaries
+                    for other in &self.other_results {
+                        ui.separator();
+                        ui.label(egui::RichText::new(format!("Also in {}", other.source)).small().weak());
+                        if let Some(inflection) = &other.inflection {
+                            ui.label(egui::RichText::new(inflection.describe()).italics().weak());
                         }
+                        show_definition(ui, &other.definition);
                     }
-
-                    ui.separator();
-
-                    if let Some(comparisons) = &definition.comparisons {
-                        for (_, comparison) in comparisons.iter().enumerate() {
-                            // English comparison word
-                            ui.label(format!("  {}", comparison.word));
-                            if let Some(analysis) = &comparison.analysis {
-                                ui.label(
-                                    egui::RichText::new(format!("{}", analysis))
-                                        .color(egui::Color32::from_rgb(0, 100, 0)),
-                                );
+                } else if !self.reverse_results.is_empty() {
+                    // Chinese-to-English results, best match first
+                    for (word, concise) in &self.reverse_results {
+                        ui.horizontal(|ui| {
+                            if ui.link(word).clicked() {
+                                clicked_entry = Some(word.clone());
+                            }
+                            ui.colored_label(egui::Color32::from_rgb(0, 100, 0), concise);
+                        });
+                    }
+                } else if self.full_text_mode && !self.full_text_results.is_empty() {
+                    // Full-text results with the matched field and a highlighted snippet
+                    for hit in &self.full_text_results {
+                        ui.horizontal(|ui| {
+                            if ui.link(egui::RichText::new(&hit.word).strong()).clicked() {
+                                clicked_entry = Some(hit.word.clone());
+                            }
+                            ui.label(egui::RichText::new(hit.field.label()).weak());
+                            if self.dictionary.has_multiple_sources() {
+                                ui.label(egui::RichText::new(&hit.source).small().weak());
                             }
-                            ui.add_space(10.0);
+                        });
+                        if !hit.snippet.is_empty() {
+                            ui.label(snippet_layout(ui, hit));
                         }
+                        ui.add_space(6.0);
                     }
                 } else if !self.search_term.is_empty() {
                     ui.label("Enter a word and click Search to look it up.");
                 }
             });
+
+            // Open the entry clicked in a results list
+            if let Some(word) = clicked_entry {
+                self.open_entry(word);
+            }
         });
+
+        if self.show_build_report {
+            self.show_build_report_window(ctx);
+        }
+    }
+}
+
+// Status message when the user cancelled a build
+const CANCELLED_MESSAGE: &str = "Index build cancelled; the previous index is unchanged";
+
+// Status message for a finished sync
+fn sync_message(summary: &SyncSummary) -> String {
+    format!(
+        "Index synced successfully! {} added, {} updated, {} removed, {} unchanged ({} errors){}",
+        summary.added,
+        summary.updated,
+        summary.removed,
+        summary.unchanged,
+        summary.errors,
+        report_hint(summary)
+    )
+}
+
+// Points at the build report when the build found problems
+fn report_hint(summary: &SyncSummary) -> String {
+    match summary.issues.len() {
+        0 => String::new(),
+        1 => " - 1 problem, see Details".to_string(),
+        count => format!(" - {} problems, see Details", count),
+    }
+}
+
+// Lines of the issue's file around the error (the start of the file when there is no line number).
+// Packs are decompressed like when indexing.
+fn read_excerpt(issue: &BuildIssue) -> Vec<(usize, String)> {
+    let path = Path::new(&issue.file);
+    let reader: Box<dyn BufRead> = if pack::is_pack(path) {
+        match pack::open_pack(path) {
+            Ok(reader) => reader,
+            Err(_) => return Vec::new(),
+        }
+    } else {
+        match File::open(path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(_) => return Vec::new(),
+        }
+    };
+
+    let (first, last) = match issue.line {
+        Some(line) => (line.saturating_sub(EXCERPT_CONTEXT_LINES).max(1), line + EXCERPT_CONTEXT_LINES),
+        None => (1, 1 + 2 * EXCERPT_CONTEXT_LINES),
+    };
+    reader
+        .lines()
+        .map_while(Result::ok)
+        .enumerate()
+        .map(|(i, text)| (i + 1, text))
+        .skip(first - 1)
+        .take(last - first + 1)
+        .map(|(number, text)| {
+            let text = match text.char_indices().nth(EXCERPT_MAX_CHARS) {
+                Some((end, _)) => format!("{}…", &text[..end]),
+                None => text,
+            };
+            (number, text)
+        })
+        .collect()
+}
+
+// Render an entry: headword, pronunciation, concise definition, meanings and comparisons
+fn show_definition(ui: &mut egui::Ui, definition: &WordDefinition) {
+    // Display word information
+    ui.heading(&definition.word);
+    if let Some(phonetic) = &definition.phonetic {
+        if !phonetic.is_empty() {
+            ui.label(format!("/{}/", phonetic));
+        }
+    }
+
+    // Display concise definition if available with better styling
+    if let Some(concise_def) = &definition.concise_definition {
+        ui.horizontal(|ui| {
+            ui.colored_label(egui::Color32::from_rgb(0, 100, 0), concise_def);
+        });
+    }
+
+    ui.separator();
+
+    // Display meanings
+    if let Some(meanings) = &definition.meanings {
+        for (i, meaning) in meanings.iter().enumerate() {
+            // Part of speech with color
+            ui.colored_label(
+                egui::Color32::DARK_BLUE,
+                format!("{}. {}", i + 1, meaning.part_of_speech),
+            );
+
+            // English explanation
+            ui.label(format!("{}", meaning.explanation_en));
+            // Transaltion explanation with color
+            if let Some(explanation_cn) = &meaning.explanation_cn {
+                ui.colored_label(
+                    egui::Color32::from_rgb(0, 100, 0),
+                    explanation_cn,
+                );
+            }
+
+            // English example with italic style
+            if let Some(example) = &meaning.example_en {
+                ui.horizontal(|ui| {
+                    ui.add_space(10.0);
+                    ui.label(egui::RichText::new(example).italics());
+                });
+            }
+
+            // Chinese example with color and italic style
+            if let Some(example_cn) = &meaning.example_cn {
+                ui.horizontal(|ui| {
+                    ui.add_space(10.0);
+                    ui.label(
+                        egui::RichText::new(example_cn)
+                            .color(egui::Color32::from_rgb(0, 100, 0)).
+                            italics()
+                    );
+                });
+            }
+
+            ui.add_space(10.0);
+        }
+    }
+
+    ui.separator();
+
+    if let Some(comparisons) = &definition.comparisons {
+        for (_, comparison) in comparisons.iter().enumerate() {
+            // English comparison word
+            ui.label(format!("  {}", comparison.word));
+            if let Some(analysis) = &comparison.analysis {
+                ui.label(
+                    egui::RichText::new(format!("{}", analysis))
+                        .color(egui::Color32::from_rgb(0, 100, 0)),
+                );
+            }
+            ui.add_space(10.0);
+        }
+    }
+}
+
+// Render a full-text snippet with its matched terms highlighted
+fn snippet_layout(ui: &egui::Ui, hit: &FullTextHit) -> egui::text::LayoutJob {
+    let font_id = egui::TextStyle::Body.resolve(ui.style());
+    let normal = egui::TextFormat {
+        font_id: font_id.clone(),
+        color: ui.visuals().text_color(),
+        ..Default::default()
+    };
+    let highlighted = egui::TextFormat {
+        font_id,
+        color: ui.visuals().strong_text_color(),
+        background: egui::Color32::from_rgb(255, 230, 150),
+        ..Default::default()
+    };
+
+    let mut job = egui::text::LayoutJob::default();
+    let mut pos = 0;
+    for range in &hit.highlights {
+        if range.start < pos || range.end > hit.snippet.len() {
+            continue;
+        }
+        job.append(&hit.snippet[pos..range.start], 0.0, normal.clone());
+        job.append(&hit.snippet[range.clone()], 0.0, highlighted.clone());
+        pos = range.end;
     }
+    job.append(&hit.snippet[pos..], 0.0, normal);
+    job.wrap.max_width = ui.available_width();
+    job
 }
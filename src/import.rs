@@ -0,0 +1,426 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::cjk;
+use crate::models::{normalize_headword, Meaning, WordDefinition};
+use crate::mdict;
+use crate::pack;
+use crate::stardict;
+
+// 导入其他格式的词典：转换为 words 目录中的 WordDefinition JSON 文件，之后像普通词条一样建立索引
+
+// 词性缩写（StarDict、MDict 等词典的释义行常以 "vt." "adj." 开头）与 words 中使用的词性名称
+const PARTS_OF_SPEECH: [(&str, &str); 22] = [
+    ("n", "noun"),
+    ("v", "verb"),
+    ("vt", "verb"),
+    ("vi", "verb"),
+    ("adj", "adjective"),
+    ("a", "adjective"),
+    ("adv", "adverb"),
+    ("ad", "adverb"),
+    ("prep", "preposition"),
+    ("conj", "conjunction"),
+    ("pron", "pronoun"),
+    ("int", "interjection"),
+    ("interj", "interjection"),
+    ("num", "numeral"),
+    ("art", "article"),
+    ("abbr", "abbreviation"),
+    ("aux", "auxiliary verb"),
+    ("det", "determiner"),
+    ("phr", "phrase"),
+    ("pl", "plural"),
+    ("pref", "prefix"),
+    ("suf", "suffix"),
+];
+
+// 简明释义最多取前几行释义
+const CONCISE_LINES: usize = 3;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportSummary {
+    // 源词典中的词条数（同一词头的多个词条合并为一个文件）
+    pub entries: usize,
+    pub written: usize,
+    // 目标目录中已存在、未覆盖的文件
+    pub skipped_existing: usize,
+    // 因词头相同而合并的词条
+    pub merged: usize,
+    // 文件名与另一个词头冲突（例如 "AC/DC" 和 "AC:DC"）、改用带序号的文件名写入的词条
+    pub renamed: usize,
+}
+
+// 按扩展名识别格式（包括词典包，即把词典包解开为 words 目录），把 path 中的词典转换后写入 words_dir
+pub fn import_file(path: &Path, words_dir: &Path, overwrite: bool) -> Result<ImportSummary, Box<dyn Error>> {
+    let extension = path.extension().map(|e| e.to_string_lossy().to_ascii_lowercase());
+    let definitions = match extension.as_deref() {
+        _ if pack::is_pack(path) => pack::read_definitions(path)?,
+        Some("ifo") => stardict::read_definitions(path)?,
+        Some("mdx") => mdict::read_definitions(path)?,
+        _ if path.is_dir() => stardict::read_definitions(path)?,
+        _ => {
+            return Err(format!(
+                "Unsupported dictionary format: {} (expected a StarDict .ifo, MDict .mdx or .jsonl pack file)",
+                path.display()
+            )
+            .into());
+        }
+    };
+    Ok(write_definitions(definitions, words_dir, overwrite)?)
+}
+
+// 写入词条文件，同一词头（按 normalize_headword，"Polish" 与 "polish" 相同）的词条合并义项，
+// 文件中的词头用第一次出现的写法；已存在的文件只在 overwrite 时覆盖
+pub fn write_definitions(
+    definitions: impl IntoIterator<Item = WordDefinition>,
+    words_dir: &Path,
+    overwrite: bool,
+) -> io::Result<ImportSummary> {
+    let mut summary = ImportSummary::default();
+    let mut merged: Vec<WordDefinition> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for definition in definitions {
+        summary.entries += 1;
+        let headword = normalize_headword(&definition.word);
+        match positions.get(&headword) {
+            Some(&position) => {
+                merge_definition(&mut merged[position], definition);
+                summary.merged += 1;
+            }
+            None => {
+                positions.insert(headword, merged.len());
+                merged.push(definition);
+            }
+        }
+    }
+
+    fs::create_dir_all(words_dir)?;
+    let mut used_names = HashSet::new();
+    for definition in &merged {
+        let (path, renamed) = unique_path(words_dir, &definition.word, &mut used_names);
+        if renamed {
+            summary.renamed += 1;
+        }
+        if path.exists() && !overwrite {
+            summary.skipped_existing += 1;
+            continue;
+        }
+        let json = serde_json::to_string_pretty(definition).map_err(io::Error::other)?;
+        fs::write(&path, json)?;
+        summary.written += 1;
+    }
+    Ok(summary)
+}
+
+// 为词头选择不与其他词头冲突的文件名："AC/DC" 和 "AC:DC" 会得到相同的文件名，不区分大小写的文件系统上
+// 只有大小写不同的文件名也是同一个文件。本次导入中已用过（忽略大小写）的文件名，
+// 以及已存在且属于另一个词头的文件，都改用 "AC_DC (2).json" 这样的文件名；返回是否改过名
+fn unique_path(words_dir: &Path, word: &str, used_names: &mut HashSet<String>) -> (PathBuf, bool) {
+    let file_name = file_name_for(word);
+    let stem = file_name.trim_end_matches(".json");
+    let mut n = 1;
+    loop {
+        let candidate = if n == 1 { file_name.clone() } else { format!("{} ({}).json", stem, n) };
+        let path = words_dir.join(&candidate);
+        let taken = used_names.contains(&candidate.to_lowercase())
+            || existing_word(&path).is_some_and(|existing| normalize_headword(&existing) != normalize_headword(word));
+        if !taken {
+            used_names.insert(candidate.to_lowercase());
+            return (path, n > 1);
+        }
+        n += 1;
+    }
+}
+
+// 已存在的词条文件中的词头；文件不存在或无法解析时为 None（仍按已存在的文件处理）
+fn existing_word(path: &Path) -> Option<String> {
+    let data = fs::read_to_string(path).ok()?;
+    serde_json::from_str::<WordDefinition>(&data).ok().map(|definition| definition.word)
+}
+
+fn merge_definition(target: &mut WordDefinition, other: WordDefinition) {
+    if target.phonetic.is_none() {
+        target.phonetic = other.phonetic;
+    }
+    match (&mut target.concise_definition, other.concise_definition) {
+        (Some(concise), Some(other)) if !other.is_empty() => {
+            concise.push_str("; ");
+            concise.push_str(&other);
+        }
+        (None, other) => target.concise_definition = other,
+        _ => {}
+    }
+    if let Some(meanings) = other.meanings {
+        target.meanings.get_or_insert_with(Vec::new).extend(meanings);
+    }
+    for (kind, value) in other.forms.into_iter().flatten() {
+        let forms = target.forms.get_or_insert_with(HashMap::new);
+        match (forms.get_mut(&kind), value) {
+            (Some(Value::Array(existing)), Value::Array(more)) => existing.extend(more),
+            (Some(_), _) => {}
+            (None, value) => {
+                forms.insert(kind, value);
+            }
+        }
+    }
+}
+
+// 词头对应的文件名：words 中以词头命名，文件名中不允许的字符替换为 "_"
+pub fn file_name_for(word: &str) -> String {
+    let name: String = word
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    // 不能以 "." 开头（隐藏文件，也会与 .index 等目录混淆）
+    let name = name.trim_start_matches('.');
+    let name = if name.is_empty() { "_" } else { name };
+    format!("{}.json", name)
+}
+
+// 把纯文本释义转换为 WordDefinition：
+// 音标行（/.../ 或 [...]）作为 pronunciation，其余每行一个义项，行首的词性缩写作为 pos，
+// 英文部分作为 explanation_en、从第一个汉字开始的部分作为 explanation_cn；前几行合起来作为简明释义
+pub fn definition_from_text(word: &str, text: &str, phonetic: Option<String>) -> WordDefinition {
+    let mut phonetic = phonetic.filter(|p| !p.is_empty());
+    let mut lines = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim().trim_start_matches('*').trim();
+        if line.is_empty() || line == word {
+            continue;
+        }
+        if phonetic.is_none()
+            && let Some(p) = bracketed_phonetic(line)
+        {
+            phonetic = Some(p.to_string());
+            continue;
+        }
+        lines.push(line);
+    }
+
+    let meanings: Vec<Meaning> = lines
+        .iter()
+        .map(|line| {
+            let (part_of_speech, rest) = split_part_of_speech(line);
+            let (explanation_en, explanation_cn) = split_languages(rest);
+            Meaning {
+                part_of_speech: part_of_speech.to_string(),
+                explanation_en,
+                explanation_cn,
+                example_en: None,
+                example_cn: None,
+            }
+        })
+        .collect();
+
+    let concise = lines.iter().take(CONCISE_LINES).copied().collect::<Vec<_>>().join("; ");
+
+    WordDefinition {
+        word: word.to_string(),
+        phonetic,
+        forms: None,
+        concise_definition: Some(concise).filter(|c| !c.is_empty()),
+        meanings: Some(meanings).filter(|m| !m.is_empty()),
+        comparisons: None,
+    }
+}
+
+// 整行是 /.../ 或 [...] 时返回其中的音标
+fn bracketed_phonetic(line: &str) -> Option<&str> {
+    let inner = line
+        .strip_prefix('/')
+        .and_then(|rest| rest.strip_suffix('/'))
+        .or_else(|| line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')))?;
+    (!inner.is_empty() && !inner.contains(['/', '[', ']']) && !cjk::contains_cjk(inner)).then_some(inner.trim())
+}
+
+// "vt. 放弃" -> ("verb", "放弃")；没有可识别的词性缩写时 pos 为空
+fn split_part_of_speech(line: &str) -> (&'static str, &str) {
+    if let Some((abbreviation, rest)) = line.split_once('.') {
+        let abbreviation = abbreviation.trim();
+        if let Some((_, name)) = PARTS_OF_SPEECH.iter().find(|(short, _)| *short == abbreviation) {
+            return (name, rest.trim());
+        }
+    }
+    ("", line)
+}
+
+// "to give up; 放弃" -> ("to give up", Some("放弃"))；纯中文时英文部分为空
+fn split_languages(text: &str) -> (String, Option<String>) {
+    match text.find(cjk::is_cjk) {
+        Some(start) => {
+            let english = text[..start].trim().trim_end_matches([';', ',', '；', '，', ':', '：']).trim();
+            (english.to_string(), Some(text[start..].trim().to_string()))
+        }
+        None => (text.trim().to_string(), None),
+    }
+}
+
+// HTML 词条转纯文本：块级标签和 <br> 换行，去掉其余标签、<script>/<style> 的内容，解码常用实体
+pub fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&decode_entities(&rest[..start]));
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        if !tag.starts_with('/') && (name == "script" || name == "style") {
+            let closing = format!("</{}", name);
+            match rest.to_ascii_lowercase().find(&closing) {
+                Some(position) => {
+                    rest = &rest[position..];
+                    if let Some(end) = rest.find('>') {
+                        rest = &rest[end + 1..];
+                    }
+                }
+                None => rest = "",
+            }
+            continue;
+        }
+        if matches!(name.as_str(), "br" | "p" | "div" | "li" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6") {
+            text.push('\n');
+        }
+    }
+    text.push_str(&decode_entities(rest));
+
+    // 合并多余的空白行
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub(crate) fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate;
+
+    fn definition(word: &str, concise: &str) -> WordDefinition {
+        serde_json::from_value(serde_json::json!({ "word": word, "concise_definition": concise })).unwrap()
+    }
+
+    fn word_in(path: &Path) -> String {
+        existing_word(path).unwrap()
+    }
+
+    #[test]
+    fn headwords_differing_in_case_are_merged() {
+        let temp = tempfile::tempdir().unwrap();
+        let definitions = [definition("Polish", "adj. 波兰的"), definition("polish", "v. 擦亮"), definition("POLISH", "")];
+
+        let summary = write_definitions(definitions.clone(), temp.path(), false).unwrap();
+        assert_eq!((summary.entries, summary.merged, summary.written, summary.renamed), (3, 2, 1, 0));
+        assert_eq!(fs::read_dir(temp.path()).unwrap().count(), 1);
+        let data = fs::read_to_string(temp.path().join("Polish.json")).unwrap();
+        let merged: WordDefinition = serde_json::from_str(&data).unwrap();
+        assert_eq!(merged.word, "Polish");
+        assert_eq!(merged.concise_definition.as_deref(), Some("adj. 波兰的; v. 擦亮"));
+
+        // 导入的结果能通过校验，不会有重复词头
+        let report = validate::validate(temp.path()).unwrap();
+        assert_eq!((report.entries, report.errors, report.warnings), (1, 0, 0));
+
+        // 再次导入时找到上次写入的文件
+        let summary = write_definitions(definitions, temp.path(), false).unwrap();
+        assert_eq!((summary.written, summary.skipped_existing), (0, 1));
+    }
+
+    #[test]
+    fn headwords_mapping_to_the_same_file_name_get_separate_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let summary = write_definitions([definition("AC/DC", ""), definition("AC:DC", "")], temp.path(), true).unwrap();
+        assert_eq!((summary.written, summary.renamed), (2, 1));
+        assert_eq!(word_in(&temp.path().join("AC_DC.json")), "AC/DC");
+        assert_eq!(word_in(&temp.path().join("AC_DC (2).json")), "AC:DC");
+    }
+
+    #[test]
+    fn existing_file_of_another_headword_is_not_overwritten() {
+        let temp = tempfile::tempdir().unwrap();
+        write_definitions([definition("AC/DC", "band")], temp.path(), false).unwrap();
+
+        let summary = write_definitions([definition("AC:DC", "")], temp.path(), true).unwrap();
+        assert_eq!((summary.written, summary.renamed), (1, 1));
+        assert_eq!(word_in(&temp.path().join("AC_DC.json")), "AC/DC");
+    }
+
+    #[test]
+    fn entries_with_the_same_headword_are_merged() {
+        let temp = tempfile::tempdir().unwrap();
+        let summary = write_definitions([definition("give", "v. 给"), definition("give", "n. 弹性")], temp.path(), false).unwrap();
+        assert_eq!((summary.entries, summary.merged, summary.written, summary.renamed), (2, 1, 1, 0));
+
+        let data = fs::read_to_string(temp.path().join("give.json")).unwrap();
+        let merged: WordDefinition = serde_json::from_str(&data).unwrap();
+        assert_eq!(merged.concise_definition.as_deref(), Some("v. 给; n. 弹性"));
+    }
+}
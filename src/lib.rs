@@ -1,3 +1,20 @@
 pub mod models;
+pub mod autocomplete;
+pub mod cjk;
+pub mod config;
 pub mod dictionary;
+pub mod dictionary_set;
+pub mod manifest;
+pub mod pack;
+pub mod render;
+pub mod scan;
+pub mod batch;
+pub mod import;
+pub mod mdict;
+pub mod stardict;
+pub mod validate;
+pub mod server;
+pub mod dict_server;
 pub mod gui;
+pub mod tui;
+pub mod watcher;
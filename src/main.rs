@@ -1,16 +1,39 @@
 // Windows 特定配置：隐藏控制台窗口
 #![cfg_attr(target_os = "windows", windows_subsystem = "windows")]
 
-mod models;
-mod dictionary;
-mod gui;
+use std::path::PathBuf;
 
-use dictionary::Dictionary;
+use DictNavi::config::Config;
+use DictNavi::gui;
+use clap::Parser;
 use eframe::egui;
 
+/// DictNavi - English Dictionary
+#[derive(Parser)]
+#[command(version)]
+struct Args {
+    /// Directory containing the word JSON files (overrides the config file)
+    #[arg(long)]
+    words_dir: Option<PathBuf>,
+
+    /// Writable directory for the index and caches (overrides the config file)
+    #[arg(long)]
+    index_dir: Option<PathBuf>,
+
+    /// Config file to use instead of <config dir>/dictnavi/config.toml
+    #[arg(long)]
+    config: Option<PathBuf>,
+}
+
 #[tokio::main]
 async fn main() -> Result<(), eframe::Error> {
-    let dict = Dictionary::new("words".to_string());
+    let args = Args::parse();
+    // 配置文件无效时给出提示并使用默认位置
+    let config = Config::load(args.config.as_deref()).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        Config::default()
+    });
+    let dict = config.dictionary_set(args.words_dir, args.index_dir);
     
     let options = eframe::NativeOptions {
         viewport: eframe::egui::ViewportBuilder::default()
@@ -51,7 +74,7 @@ async fn main() -> Result<(), eframe::Error> {
             cc.egui_ctx.set_fonts(fonts);
             
             // 在闭包内部创建 app，并直接返回 Box
-            let app = gui::DictNaviApp::new(dict);
+            let app = gui::DictNaviApp::new(cc, dict);
             Box::new(app)
         }),
     )
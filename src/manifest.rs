@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use crate::dictionary::BuildIssue;
+
+// 清单文件与 tantivy 索引文件放在同一目录下
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+// 清单格式版本，格式变化时递增以触发全量重建
+const MANIFEST_VERSION: u32 = 1;
+
+// 单个 JSON 文件在上次同步时的状态
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ManifestEntry {
+    pub mtime_ns: u64,
+    pub size: u64,
+    pub hash: String,
+    pub word: String,
+}
+
+// 词典包文件在上次同步时的状态；没有变化时不需要读取词典包
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PackState {
+    pub mtime_ns: u64,
+    pub size: u64,
+}
+
+// 上次同步时无法建立索引的文件（词典包中为行）：文件没有变化时不再读取，只重复报告原因。
+// skipped 为构建报告中列出的 文件 或 包文件:行号
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FailedEntry {
+    pub mtime_ns: u64,
+    pub size: u64,
+    pub skipped: String,
+    pub issue: BuildIssue,
+}
+
+// 索引清单：记录每个已索引文件（相对 words 目录的路径）的修改时间、大小和内容哈希；
+// 来源为词典包时每个词条一项，键为词头
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IndexManifest {
+    pub version: u32,
+    pub entries: BTreeMap<String, ManifestEntry>,
+    // 解析失败或无法读取的文件，与 entries 的键不重叠
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub failed: BTreeMap<String, FailedEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pack: Option<PackState>,
+}
+
+impl Default for IndexManifest {
+    fn default() -> Self {
+        IndexManifest {
+            version: MANIFEST_VERSION,
+            entries: BTreeMap::new(),
+            failed: BTreeMap::new(),
+            pack: None,
+        }
+    }
+}
+
+impl IndexManifest {
+    // 读取索引目录中的清单；不存在、损坏或版本不匹配时返回 None
+    pub fn load(index_path: &str) -> Option<Self> {
+        let data = fs::read_to_string(Path::new(index_path).join(MANIFEST_FILE)).ok()?;
+        let manifest: IndexManifest = serde_json::from_str(&data).ok()?;
+        if manifest.version != MANIFEST_VERSION {
+            return None;
+        }
+        Some(manifest)
+    }
+
+    // 清单中是否记录了该文件（已索引或上次失败）
+    pub fn contains(&self, rel_path: &str) -> bool {
+        self.entries.contains_key(rel_path) || self.failed.contains_key(rel_path)
+    }
+
+    // 上次同步时记录的修改时间和大小
+    pub fn recorded_state(&self, rel_path: &str) -> Option<(u64, u64)> {
+        self.entries
+            .get(rel_path)
+            .map(|entry| (entry.mtime_ns, entry.size))
+            .or_else(|| self.failed.get(rel_path).map(|failed| (failed.mtime_ns, failed.size)))
+    }
+
+    // 先写临时文件再重命名，避免写到一半时崩溃留下损坏的清单
+    pub fn save(&self, index_path: &str) -> std::io::Result<()> {
+        let final_path = Path::new(index_path).join(MANIFEST_FILE);
+        let tmp_path = Path::new(index_path).join(format!("{}.tmp", MANIFEST_FILE));
+        fs::write(&tmp_path, serde_json::to_vec(self)?)?;
+        fs::rename(&tmp_path, &final_path)
+    }
+}
+
+// 文件内容哈希（SHA-256，十六进制）
+pub fn content_hash(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+// 文件修改时间（自 UNIX 纪元起的纳秒数），无法获取时为 0
+pub fn mtime_ns(metadata: &fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,850 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use flate2::read::ZlibDecoder;
+use serde_json::Value;
+
+use crate::import;
+use crate::models::WordDefinition;
+
+// MDict 词典（.mdx）的读取，支持 1.x 和 2.x 格式：
+// 头部（UTF-16 的 XML 属性）、词头块信息、词头块（词头与记录偏移）、记录块信息、记录块（释义，通常为 HTML）。
+// 各数据块可以不压缩、LZO 或 zlib 压缩；加密只支持不需要注册码的词头块信息加密（Encrypted="2"）。
+
+// 指向其他词头的记录，如 colour 的记录为 "@@@LINK=color"
+const LINK_PREFIX: &str = "@@@LINK=";
+// 链接到某词条的词头写入 forms 的这个键，查询时通过屈折形式的机制找到原词条
+const VARIANT_FORM: &str = "variant";
+
+#[derive(Debug, Clone, Default)]
+pub struct MdictHeader {
+    pub version: f32,
+    pub encoding: String,
+    pub encrypted: u32,
+    pub title: String,
+    // "Html" 或 "Text"
+    pub format: String,
+    pub attributes: HashMap<String, String>,
+}
+
+// 头部和全部 (词头, 记录文本)，按词头块中的顺序
+#[derive(Debug, Clone)]
+pub struct Mdict {
+    pub header: MdictHeader,
+    pub records: Vec<(String, String)>,
+}
+
+impl MdictHeader {
+    // 2.0 起数字为 8 字节
+    fn number_width(&self) -> usize {
+        if self.version >= 2.0 { 8 } else { 4 }
+    }
+
+    fn is_utf16(&self) -> bool {
+        self.encoding == "UTF-16"
+    }
+}
+
+// 读取 .mdx 中的全部词条并转换为 WordDefinition；HTML 释义去掉标签后按纯文本释义转换
+pub fn read_definitions(path: &Path) -> Result<Vec<WordDefinition>, Box<dyn Error>> {
+    let Mdict { header, records } = read_mdx(path)?;
+    let strip_style = header.attributes.get("StyleSheet").is_some_and(|s| !s.trim().is_empty());
+
+    // 先找出链接，作为目标词条的变体
+    let mut variants: HashMap<String, Vec<String>> = HashMap::new();
+    for (word, record) in &records {
+        if let Some(target) = record.trim().strip_prefix(LINK_PREFIX) {
+            let target = target.trim();
+            if target != word {
+                variants.entry(target.to_string()).or_default().push(word.clone());
+            }
+        }
+    }
+
+    let mut definitions = Vec::new();
+    for (word, record) in records {
+        if record.trim().starts_with(LINK_PREFIX) {
+            continue;
+        }
+        let record = if strip_style { strip_style_markers(&record) } else { record };
+        let text = if header.format.eq_ignore_ascii_case("Text") {
+            record
+        } else {
+            import::strip_html(&record)
+        };
+
+        let mut definition = import::definition_from_text(&word, &text, None);
+        if let Some(words) = variants.remove(&word) {
+            let words: Vec<Value> = words.into_iter().map(Value::String).collect();
+            definition.forms = Some(HashMap::from([(VARIANT_FORM.to_string(), Value::Array(words))]));
+        }
+        definitions.push(definition);
+    }
+    Ok(definitions)
+}
+
+pub fn read_mdx(path: &Path) -> Result<Mdict, Box<dyn Error>> {
+    let data = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut reader = Reader::new(&data);
+
+    let header = read_header(&mut reader).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let keys = read_keys(&mut reader, &header).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let records = read_record_data(&mut reader, &header).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    // 每条记录从它的偏移到下一个更大的偏移（最后一条到数据末尾）
+    let mut offsets: Vec<usize> = keys.iter().map(|(offset, _)| *offset).collect();
+    offsets.sort_unstable();
+    offsets.dedup();
+
+    let mut entries = Vec::with_capacity(keys.len());
+    for (offset, word) in keys {
+        let next = offsets.partition_point(|&o| o <= offset);
+        let end = offsets.get(next).copied().unwrap_or(records.len());
+        let record = records
+            .get(offset..end)
+            .ok_or_else(|| format!("{}: record of '{}' is out of range", path.display(), word))?;
+        let text = decode_text(record, &header);
+        entries.push((word, text.trim_end_matches('\0').to_string()));
+    }
+    Ok(Mdict { header, records: entries })
+}
+
+fn read_header(reader: &mut Reader) -> Result<MdictHeader, Box<dyn Error>> {
+    let size = reader.number(4)? as usize;
+    let header_bytes = reader.take(size)?;
+    let checksum = u32::from_le_bytes(reader.take(4)?.try_into()?);
+    if adler32(header_bytes) != checksum {
+        return Err("header checksum mismatch, not an MDict file".into());
+    }
+
+    let text = utf16_le(header_bytes);
+    let attributes = parse_attributes(text.trim_end_matches('\0'));
+    let version: f32 = attributes
+        .get("GeneratedByEngineVersion")
+        .and_then(|v| v.trim().parse().ok())
+        .ok_or("missing GeneratedByEngineVersion in the header")?;
+    if version >= 3.0 {
+        return Err(format!("MDict version {} is not supported", version).into());
+    }
+
+    let encoding = match attributes.get("Encoding").map(|e| e.trim().to_ascii_uppercase()) {
+        None => "UTF-8".to_string(),
+        Some(e) if e.is_empty() || e == "UTF-8" || e == "UTF8" => "UTF-8".to_string(),
+        Some(e) if e.starts_with("UTF-16") => "UTF-16".to_string(),
+        Some(e) => return Err(format!("encoding {} is not supported (only UTF-8 and UTF-16)", e).into()),
+    };
+
+    let encrypted = match attributes.get("Encrypted").map(|e| e.trim()) {
+        None | Some("") | Some("No") => 0,
+        Some("Yes") => 1,
+        Some(e) => e.parse().unwrap_or(0),
+    };
+    if encrypted & 1 != 0 {
+        return Err("the dictionary is encrypted with a registration code, which is not supported".into());
+    }
+
+    Ok(MdictHeader {
+        version,
+        encoding,
+        encrypted,
+        title: attributes.get("Title").cloned().unwrap_or_default(),
+        format: attributes.get("Format").cloned().unwrap_or_else(|| "Html".to_string()),
+        attributes,
+    })
+}
+
+// 词头部分，返回 (记录偏移, 词头)
+fn read_keys(reader: &mut Reader, header: &MdictHeader) -> Result<Vec<(usize, String)>, Box<dyn Error>> {
+    let width = header.number_width();
+    let v2 = header.version >= 2.0;
+
+    let key_header_start = reader.position;
+    let block_count = reader.number(width)? as usize;
+    let _entry_count = reader.number(width)?;
+    if v2 {
+        let _info_decompressed_size = reader.number(width)?;
+    }
+    let info_size = reader.number(width)? as usize;
+    let blocks_size = reader.number(width)? as usize;
+    if v2 {
+        let checksum = reader.number(4)? as u32;
+        if adler32(&reader.data[key_header_start..reader.position - 4]) != checksum {
+            return Err("key header checksum mismatch".into());
+        }
+    }
+
+    // 词头块信息：每块的词条数、首尾词头、压缩前后的大小
+    let info = reader.take(info_size)?;
+    let info = if v2 {
+        let info = if header.encrypted & 2 != 0 { decrypt_block(info)? } else { info.to_vec() };
+        decompress_block(&info)?
+    } else {
+        info.to_vec()
+    };
+
+    let mut sizes = Vec::with_capacity(block_count);
+    let mut info_reader = Reader::new(&info);
+    while info_reader.remaining() > 0 {
+        let _entries = info_reader.number(width)?;
+        for _ in 0..2 {
+            let length = if v2 { info_reader.number(2)? as usize } else { info_reader.number(1)? as usize };
+            // 2.x 的首尾词头带结束符
+            let length = length + usize::from(v2);
+            info_reader.take(if header.is_utf16() { length * 2 } else { length })?;
+        }
+        let compressed = info_reader.number(width)? as usize;
+        let _decompressed = info_reader.number(width)?;
+        sizes.push(compressed);
+    }
+
+    let blocks = reader.take(blocks_size)?;
+    let mut keys = Vec::new();
+    let mut start = 0;
+    for compressed in sizes {
+        let block = blocks.get(start..start + compressed).ok_or("key block is out of range")?;
+        start += compressed;
+
+        let block = decompress_block(block)?;
+        let mut block_reader = Reader::new(&block);
+        while block_reader.remaining() > 0 {
+            let offset = block_reader.number(width)? as usize;
+            let text = block_reader.terminated(header.is_utf16())?;
+            keys.push((offset, decode_text(text, header)));
+        }
+    }
+    Ok(keys)
+}
+
+// 记录部分：全部记录块解压后连在一起，词头中的偏移指向这段数据
+fn read_record_data(reader: &mut Reader, header: &MdictHeader) -> Result<Vec<u8>, Box<dyn Error>> {
+    let width = header.number_width();
+    let block_count = reader.number(width)? as usize;
+    let _entry_count = reader.number(width)?;
+    let _info_size = reader.number(width)?;
+    let _blocks_size = reader.number(width)?;
+
+    let mut sizes = Vec::with_capacity(block_count);
+    for _ in 0..block_count {
+        let compressed = reader.number(width)? as usize;
+        let decompressed = reader.number(width)? as usize;
+        sizes.push((compressed, decompressed));
+    }
+
+    let mut records = Vec::new();
+    for (compressed, decompressed) in sizes {
+        let block = decompress_block(reader.take(compressed)?)?;
+        if block.len() != decompressed {
+            return Err("record block has an unexpected size".into());
+        }
+        records.extend_from_slice(&block);
+    }
+    Ok(records)
+}
+
+// 数据块：4 字节压缩类型（0 不压缩，1 LZO，2 zlib）、4 字节解压后数据的 Adler-32、数据
+fn decompress_block(block: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    if block.len() < 8 {
+        return Err("truncated block".into());
+    }
+    let kind = u32::from_le_bytes(block[0..4].try_into()?);
+    let checksum = u32::from_be_bytes(block[4..8].try_into()?);
+    let data = &block[8..];
+
+    let decompressed = match kind {
+        0 => data.to_vec(),
+        1 => lzo1x_decompress(data)?,
+        2 => {
+            let mut decompressed = Vec::new();
+            ZlibDecoder::new(data)
+                .read_to_end(&mut decompressed)
+                .map_err(|e| format!("failed to decompress block: {}", e))?;
+            decompressed
+        }
+        other => return Err(format!("unknown block compression type {}", other).into()),
+    };
+
+    if adler32(&decompressed) != checksum {
+        return Err("block checksum mismatch".into());
+    }
+    Ok(decompressed)
+}
+
+// Encrypted="2"：词头块信息的数据部分用 RIPEMD-128(校验和 + 0x3695) 作为密钥加密
+fn decrypt_block(block: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    if block.len() < 8 {
+        return Err("truncated block".into());
+    }
+    let mut key_source = block[4..8].to_vec();
+    key_source.extend_from_slice(&0x3695u32.to_le_bytes());
+    let key = ripemd128(&key_source);
+
+    let mut decrypted = block[..8].to_vec();
+    let mut previous = 0x36u8;
+    for (i, &byte) in block[8..].iter().enumerate() {
+        let value = byte.rotate_left(4) ^ previous ^ (i as u8) ^ key[i % key.len()];
+        previous = byte;
+        decrypted.push(value);
+    }
+    Ok(decrypted)
+}
+
+fn decode_text(bytes: &[u8], header: &MdictHeader) -> String {
+    if header.is_utf16() {
+        utf16_le(bytes)
+    } else {
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+fn utf16_le(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+    String::from_utf16_lossy(&units)
+}
+
+// 头部为一个 XML 标签，如 <Dictionary GeneratedByEngineVersion="2.0" Encoding="UTF-8" .../>
+fn parse_attributes(tag: &str) -> HashMap<String, String> {
+    let parts: Vec<&str> = tag.split('"').collect();
+    parts
+        .chunks_exact(2)
+        .filter_map(|pair| {
+            let name = pair[0].trim_end().strip_suffix('=')?.split_whitespace().last()?;
+            Some((name.to_string(), import::decode_entities(pair[1])))
+        })
+        .collect()
+}
+
+// 使用 StyleSheet 的词典在记录中以 `1`、`2` 等标记样式，转换前去掉
+fn strip_style_markers(record: &str) -> String {
+    let mut out = String::with_capacity(record.len());
+    let mut parts = record.split('`');
+    if let Some(first) = parts.next() {
+        out.push_str(first);
+    }
+    let mut inside = true;
+    for part in parts {
+        if !(inside && !part.is_empty() && part.chars().all(|c| c.is_ascii_digit())) {
+            if inside {
+                out.push('`');
+            }
+            out.push_str(part);
+        }
+        inside = !inside;
+    }
+    out
+}
+
+// 从数据中按顺序读取数字（大端）和字符串
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Reader { data, position: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.position
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], Box<dyn Error>> {
+        let bytes = self
+            .data
+            .get(self.position..self.position + length)
+            .ok_or("unexpected end of data")?;
+        self.position += length;
+        Ok(bytes)
+    }
+
+    fn number(&mut self, width: usize) -> Result<u64, Box<dyn Error>> {
+        Ok(self.take(width)?.iter().fold(0u64, |n, &b| (n << 8) | u64::from(b)))
+    }
+
+    // 以 \0（UTF-16 为对齐的 \0\0）结尾的字符串，不含结束符
+    fn terminated(&mut self, utf16: bool) -> Result<&'a [u8], Box<dyn Error>> {
+        let rest = &self.data[self.position..];
+        let end = if utf16 {
+            rest.chunks_exact(2).position(|pair| pair == [0, 0]).map(|i| i * 2)
+        } else {
+            rest.iter().position(|&b| b == 0)
+        }
+        .ok_or("unterminated key text")?;
+        let text = &rest[..end];
+        self.position += end + if utf16 { 2 } else { 1 };
+        Ok(text)
+    }
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += u32::from(byte);
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (b << 16) | a
+}
+
+// LZO1X 解压（与 lzo1x_decompress_safe 相同的格式），越界时返回错误
+fn lzo1x_decompress(input: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    enum State {
+        Literal,
+        FirstLiteralRun,
+        Match(usize),
+        MatchNext(usize),
+    }
+
+    let mut reader = Reader::new(input);
+    let mut out: Vec<u8> = Vec::with_capacity(input.len() * 3);
+
+    fn byte(reader: &mut Reader) -> Result<usize, Box<dyn Error>> {
+        Ok(reader.take(1)?[0] as usize)
+    }
+    // 长度为 0 时后面是若干个 0（每个加 255）和一个非 0 字节
+    fn run_length(reader: &mut Reader, base: usize) -> Result<usize, Box<dyn Error>> {
+        let mut length = 0;
+        loop {
+            match byte(reader)? {
+                0 => length += 255,
+                b => return Ok(length + base + b),
+            }
+        }
+    }
+    fn le16(reader: &mut Reader) -> Result<usize, Box<dyn Error>> {
+        let bytes = reader.take(2)?;
+        Ok(bytes[0] as usize | (bytes[1] as usize) << 8)
+    }
+    fn copy_match(out: &mut Vec<u8>, distance: usize, length: usize) -> Result<(), Box<dyn Error>> {
+        let start = out.len().checked_sub(distance).ok_or("LZO match points before the start of the output")?;
+        for i in 0..length {
+            out.push(out[start + i]);
+        }
+        Ok(())
+    }
+    // 匹配后紧跟的字面量个数在指令倒数第二个字节的低 2 位
+    fn after_match(reader: &Reader) -> State {
+        match reader.data[reader.position - 2] & 3 {
+            0 => State::Literal,
+            t => State::MatchNext(t as usize),
+        }
+    }
+
+    let mut state = State::Literal;
+    if input.first().is_some_and(|&b| b > 17) {
+        let t = byte(&mut reader)? - 17;
+        if t < 4 {
+            state = State::MatchNext(t);
+        } else {
+            out.extend_from_slice(reader.take(t)?);
+            state = State::FirstLiteralRun;
+        }
+    }
+
+    loop {
+        state = match state {
+            State::Literal => {
+                let t = byte(&mut reader)?;
+                if t >= 16 {
+                    State::Match(t)
+                } else {
+                    let t = if t == 0 { run_length(&mut reader, 15)? } else { t };
+                    out.extend_from_slice(reader.take(t + 3)?);
+                    State::FirstLiteralRun
+                }
+            }
+            State::FirstLiteralRun => {
+                let t = byte(&mut reader)?;
+                if t >= 16 {
+                    State::Match(t)
+                } else {
+                    let distance = 1 + 0x0800 + (t >> 2) + (byte(&mut reader)? << 2);
+                    copy_match(&mut out, distance, 3)?;
+                    after_match(&reader)
+                }
+            }
+            State::Match(t) => {
+                let (distance, length) = if t >= 64 {
+                    let distance = 1 + ((t >> 2) & 7) + (byte(&mut reader)? << 3);
+                    (distance, (t >> 5) + 1)
+                } else if t >= 32 {
+                    let length = match t & 31 {
+                        0 => run_length(&mut reader, 31)?,
+                        length => length,
+                    };
+                    let offset = le16(&mut reader)?;
+                    (1 + (offset >> 2), length + 2)
+                } else if t >= 16 {
+                    let length = match t & 7 {
+                        0 => run_length(&mut reader, 7)?,
+                        length => length,
+                    };
+                    let offset = le16(&mut reader)?;
+                    let distance = ((t & 8) << 11) + (offset >> 2);
+                    if distance == 0 {
+                        // 结束标记
+                        return Ok(out);
+                    }
+                    (distance + 0x4000, length + 2)
+                } else {
+                    (1 + (t >> 2) + (byte(&mut reader)? << 2), 2)
+                };
+                copy_match(&mut out, distance, length)?;
+                after_match(&reader)
+            }
+            State::MatchNext(t) => {
+                out.extend_from_slice(reader.take(t)?);
+                State::Match(byte(&mut reader)?)
+            }
+        };
+    }
+}
+
+// RIPEMD-128，只用于 Encrypted="2" 的密钥
+fn ripemd128(message: &[u8]) -> [u8; 16] {
+    const R: [[usize; 16]; 4] = [
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+        [7, 4, 13, 1, 10, 6, 15, 3, 12, 0, 9, 5, 2, 14, 11, 8],
+        [3, 10, 14, 4, 9, 15, 8, 1, 2, 7, 0, 6, 13, 11, 5, 12],
+        [1, 9, 11, 10, 0, 8, 12, 4, 13, 3, 7, 15, 14, 5, 6, 2],
+    ];
+    const R_PRIME: [[usize; 16]; 4] = [
+        [5, 14, 7, 0, 9, 2, 11, 4, 13, 6, 15, 8, 1, 10, 3, 12],
+        [6, 11, 3, 7, 0, 13, 5, 10, 14, 15, 8, 12, 4, 9, 1, 2],
+        [15, 5, 1, 3, 7, 14, 6, 9, 11, 8, 12, 2, 10, 0, 4, 13],
+        [8, 6, 4, 1, 3, 11, 15, 0, 5, 12, 2, 13, 9, 7, 10, 14],
+    ];
+    const S: [[u32; 16]; 4] = [
+        [11, 14, 15, 12, 5, 8, 7, 9, 11, 13, 14, 15, 6, 7, 9, 8],
+        [7, 6, 8, 13, 11, 9, 7, 15, 7, 12, 15, 9, 11, 7, 13, 12],
+        [11, 13, 6, 7, 14, 9, 13, 15, 14, 8, 13, 6, 5, 12, 7, 5],
+        [11, 12, 14, 15, 14, 15, 9, 8, 9, 14, 5, 6, 8, 6, 5, 12],
+    ];
+    const S_PRIME: [[u32; 16]; 4] = [
+        [8, 9, 9, 11, 13, 15, 15, 5, 7, 7, 8, 11, 14, 14, 12, 6],
+        [9, 13, 15, 7, 12, 8, 9, 11, 7, 7, 12, 7, 6, 15, 13, 11],
+        [9, 7, 15, 11, 8, 6, 6, 14, 12, 13, 5, 14, 13, 13, 7, 5],
+        [15, 5, 8, 11, 14, 14, 6, 14, 6, 9, 12, 9, 12, 5, 15, 8],
+    ];
+    const K: [u32; 4] = [0x0000_0000, 0x5A82_7999, 0x6ED9_EBA1, 0x8F1B_BCDC];
+    const K_PRIME: [u32; 4] = [0x50A2_8BE6, 0x5C4D_D124, 0x6D70_3EF3, 0x0000_0000];
+
+    fn f(round: usize, x: u32, y: u32, z: u32) -> u32 {
+        match round {
+            0 => x ^ y ^ z,
+            1 => (x & y) | (!x & z),
+            2 => (x | !y) ^ z,
+            _ => (x & z) | (y & !z),
+        }
+    }
+
+    let mut padded = message.to_vec();
+    padded.push(0x80);
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&((message.len() as u64) * 8).to_le_bytes());
+
+    let mut h: [u32; 4] = [0x6745_2301, 0xEFCD_AB89, 0x98BA_DCFE, 0x1032_5476];
+    for chunk in padded.chunks_exact(64) {
+        let x: Vec<u32> = chunk
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+            .collect();
+
+        let [mut a, mut b, mut c, mut d] = h;
+        let [mut a2, mut b2, mut c2, mut d2] = h;
+        for round in 0..4 {
+            for i in 0..16 {
+                let t = a
+                    .wrapping_add(f(round, b, c, d))
+                    .wrapping_add(x[R[round][i]])
+                    .wrapping_add(K[round])
+                    .rotate_left(S[round][i]);
+                (a, b, c, d) = (d, t, b, c);
+
+                // 右路的函数顺序相反
+                let t = a2
+                    .wrapping_add(f(3 - round, b2, c2, d2))
+                    .wrapping_add(x[R_PRIME[round][i]])
+                    .wrapping_add(K_PRIME[round])
+                    .rotate_left(S_PRIME[round][i]);
+                (a2, b2, c2, d2) = (d2, t, b2, c2);
+            }
+        }
+
+        let t = h[1].wrapping_add(c).wrapping_add(d2);
+        h[1] = h[2].wrapping_add(d).wrapping_add(a2);
+        h[2] = h[3].wrapping_add(a).wrapping_add(b2);
+        h[3] = h[0].wrapping_add(b).wrapping_add(c2);
+        h[0] = t;
+    }
+
+    let mut digest = [0u8; 16];
+    for (i, word) in h.iter().enumerate() {
+        digest[i * 4..i * 4 + 4].copy_from_slice(&word.to_le_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn ripemd128_known_vectors() {
+        let vectors: [(&[u8], &str); 8] = [
+            (b"", "cdf26213a150dc3ecb610f18f6b38b46"),
+            (b"a", "86be7afa339d0fc7cfc785e72f578d33"),
+            (b"abc", "c14a12199c66e4ba84636b0f69144c77"),
+            (b"message digest", "9e327b3d6e523062afc1132d7df9d1b8"),
+            (b"abcdefghijklmnopqrstuvwxyz", "fd2aa607f71dc8f510714922b371834e"),
+            (b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq", "a1aa0689d0fafa2ddc22e88b49133a06"),
+            (b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789", "d1e959eb179c911faea4624c60c5c702"),
+            (b"12345678901234567890123456789012345678901234567890123456789012345678901234567890", "3f45ef194732c2dbb2c4a2c769795fa3"),
+        ];
+        for (message, digest) in vectors {
+            assert_eq!(hex(&ripemd128(message)), digest, "RIPEMD-128 of {:?}", String::from_utf8_lossy(message));
+        }
+    }
+
+    #[test]
+    fn adler32_known_vectors() {
+        assert_eq!(adler32(b""), 0x0000_0001);
+        assert_eq!(adler32(b"a"), 0x0062_0062);
+        assert_eq!(adler32(b"abc"), 0x024d_0127);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        // 超过 5552 字节时分块取模
+        assert_eq!(adler32(&[0xff; 100_000]), 0x149a_302c);
+    }
+
+    // LZO1X 结束标记：距离为 0 的 M4 匹配
+    const LZO_END: [u8; 3] = [0x11, 0, 0];
+
+    #[test]
+    fn lzo_literal_run() {
+        let mut input = vec![17 + 5];
+        input.extend_from_slice(b"hello");
+        input.extend_from_slice(&LZO_END);
+        assert_eq!(lzo1x_decompress(&input).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn lzo_long_literal_run() {
+        // 首字节为 0：长度 15 + 2，再加 3
+        let literals = b"twenty literal bytes";
+        let mut input = vec![0, 2];
+        input.extend_from_slice(literals);
+        input.extend_from_slice(&LZO_END);
+        assert_eq!(lzo1x_decompress(&input).unwrap(), literals);
+    }
+
+    #[test]
+    fn lzo_m3_match() {
+        // 3 个字面量，然后 M3 匹配：长度 7 + 2，距离 1 + (8 >> 2)
+        let input = [17 + 3, b'a', b'b', b'c', 32 + 7, 8, 0, 0x11, 0, 0];
+        assert_eq!(lzo1x_decompress(&input).unwrap(), b"abcabcabcabc");
+    }
+
+    #[test]
+    fn lzo_m2_match_with_trailing_literal() {
+        // M2 匹配：长度 (109 >> 5) + 1 = 4，距离 1 + ((109 >> 2) & 7) = 4，后跟 1 个字面量
+        let input = [17 + 4, b'a', b'b', b'c', b'd', 109, 0, b'!', 0x11, 0, 0];
+        assert_eq!(lzo1x_decompress(&input).unwrap(), b"abcdabcd!");
+    }
+
+    #[test]
+    fn lzo_rejects_bad_input() {
+        // 匹配的距离超出已输出的数据
+        assert!(lzo1x_decompress(&[17 + 1, b'a', 32 + 7, 40, 0, 0x11, 0, 0]).is_err());
+        // 缺少结束标记
+        assert!(lzo1x_decompress(&[17 + 5, b'h', b'e', b'l', b'l', b'o']).is_err());
+    }
+
+    #[derive(Clone, Copy)]
+    enum Packing {
+        Zlib,
+        Lzo,
+    }
+
+    // 数据块：压缩类型（小端）、解压后数据的 Adler-32（大端）、数据
+    fn block(data: &[u8], packing: Packing) -> Vec<u8> {
+        let (kind, payload) = match packing {
+            Packing::Zlib => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(data).unwrap();
+                (2u32, encoder.finish().unwrap())
+            }
+            Packing::Lzo => {
+                // 只用字面量：首字节为 0 时长度 = 15 + 若干个 255 + 末字节 + 3
+                let mut payload = vec![0];
+                let mut rest = data.len() - 18;
+                while rest > 255 {
+                    payload.push(0);
+                    rest -= 255;
+                }
+                payload.push(rest as u8);
+                payload.extend_from_slice(data);
+                payload.extend_from_slice(&LZO_END);
+                (1u32, payload)
+            }
+        };
+        let mut out = kind.to_le_bytes().to_vec();
+        out.extend_from_slice(&adler32(data).to_be_bytes());
+        out.extend_from_slice(&payload);
+        out
+    }
+
+    // decrypt_block 的逆运算
+    fn encrypt_block(block: &[u8]) -> Vec<u8> {
+        let mut key_source = block[4..8].to_vec();
+        key_source.extend_from_slice(&0x3695u32.to_le_bytes());
+        let key = ripemd128(&key_source);
+
+        let mut encrypted = block[..8].to_vec();
+        let mut previous = 0x36u8;
+        for (i, &value) in block[8..].iter().enumerate() {
+            let byte = (value ^ previous ^ (i as u8) ^ key[i % key.len()]).rotate_right(4);
+            previous = byte;
+            encrypted.push(byte);
+        }
+        encrypted
+    }
+
+    fn number(out: &mut Vec<u8>, n: usize) {
+        out.extend_from_slice(&(n as u64).to_be_bytes());
+    }
+
+    // 生成 2.0 格式、UTF-8 编码的 .mdx：一个词头块和一个记录块
+    fn mdx(entries: &[(&str, &str)], packing: Packing, encrypted: bool) -> Vec<u8> {
+        let header = format!(
+            "<Dictionary GeneratedByEngineVersion=\"2.0\" Encoding=\"UTF-8\" Encrypted=\"{}\" Format=\"Html\" Title=\"Test &amp; Co\"/>\r\n\0",
+            if encrypted { 2 } else { 0 }
+        );
+        let header: Vec<u8> = header.encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect();
+        let mut out = (header.len() as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(&header);
+        out.extend_from_slice(&adler32(&header).to_le_bytes());
+
+        let mut keys = Vec::new();
+        let mut records = Vec::new();
+        for (word, record) in entries {
+            number(&mut keys, records.len());
+            keys.extend_from_slice(word.as_bytes());
+            keys.push(0);
+            records.extend_from_slice(record.as_bytes());
+            records.push(0);
+        }
+        let key_block = block(&keys, packing);
+
+        let mut info = Vec::new();
+        number(&mut info, entries.len());
+        for word in [entries[0].0, entries[entries.len() - 1].0] {
+            info.extend_from_slice(&(word.len() as u16).to_be_bytes());
+            info.extend_from_slice(word.as_bytes());
+            info.push(0);
+        }
+        number(&mut info, key_block.len());
+        number(&mut info, keys.len());
+        let info_block = block(&info, Packing::Zlib);
+        let info_block = if encrypted { encrypt_block(&info_block) } else { info_block };
+
+        let key_header_start = out.len();
+        number(&mut out, 1);
+        number(&mut out, entries.len());
+        number(&mut out, info.len());
+        number(&mut out, info_block.len());
+        number(&mut out, key_block.len());
+        let checksum = adler32(&out[key_header_start..]);
+        out.extend_from_slice(&checksum.to_be_bytes());
+        out.extend_from_slice(&info_block);
+        out.extend_from_slice(&key_block);
+
+        let record_block = block(&records, packing);
+        number(&mut out, 1);
+        number(&mut out, entries.len());
+        number(&mut out, 16);
+        number(&mut out, record_block.len());
+        number(&mut out, record_block.len());
+        number(&mut out, records.len());
+        out.extend_from_slice(&record_block);
+        out
+    }
+
+    const ENTRIES: [(&str, &str); 3] = [
+        ("apple", "<b>n.</b> a round fruit with red or green skin"),
+        ("color", "<b>n.</b> the quality of an object that depends on the light it reflects"),
+        ("colour", "@@@LINK=color"),
+    ];
+
+    fn read_fixture(packing: Packing, encrypted: bool) -> Mdict {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.mdx");
+        fs::write(&path, mdx(&ENTRIES, packing, encrypted)).unwrap();
+        read_mdx(&path).unwrap()
+    }
+
+    fn assert_records(mdict: &Mdict) {
+        let expected: Vec<(String, String)> =
+            ENTRIES.iter().map(|(word, record)| (word.to_string(), record.to_string())).collect();
+        assert_eq!(mdict.records, expected);
+        assert_eq!(mdict.header.title, "Test & Co");
+        assert_eq!(mdict.header.version, 2.0);
+    }
+
+    #[test]
+    fn reads_zlib_mdx() {
+        assert_records(&read_fixture(Packing::Zlib, false));
+    }
+
+    #[test]
+    fn reads_lzo_mdx() {
+        assert_records(&read_fixture(Packing::Lzo, false));
+    }
+
+    #[test]
+    fn reads_mdx_with_encrypted_key_index() {
+        let mdict = read_fixture(Packing::Zlib, true);
+        assert_eq!(mdict.header.encrypted, 2);
+        assert_records(&mdict);
+    }
+
+    #[test]
+    fn rejects_corrupted_block() {
+        let mut data = mdx(&ENTRIES, Packing::Zlib, false);
+        let last = data.len() - 1;
+        data[last] ^= 0xff;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bad.mdx");
+        fs::write(&path, data).unwrap();
+        assert!(read_mdx(&path).is_err());
+    }
+
+    #[test]
+    fn links_become_variants() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.mdx");
+        fs::write(&path, mdx(&ENTRIES, Packing::Zlib, false)).unwrap();
+
+        let definitions = read_definitions(&path).unwrap();
+        let words: Vec<&str> = definitions.iter().map(|d| d.word.as_str()).collect();
+        assert_eq!(words, ["apple", "color"]);
+        let forms = definitions[1].forms.as_ref().unwrap();
+        assert_eq!(forms[VARIANT_FORM], Value::Array(vec![Value::String("colour".to_string())]));
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WordDefinition {
@@ -17,6 +17,97 @@ pub struct WordDefinition {
     pub comparisons: Option<Vec<Comparison>>,
 }
 
+// Canonical form of a headword used for exact matching: lowercased, surrounding whitespace trimmed,
+// inner whitespace collapsed to one space and typographic apostrophes/dashes folded to ASCII,
+// so "Give  up", "give up" and "rock’n’roll"/"rock'n'roll" match the same entry.
+pub fn normalize_headword(text: &str) -> String {
+    let folded: String = text
+        .chars()
+        .map(|c| match c {
+            '\u{2018}' | '\u{2019}' | '\u{02BC}' => '\'',
+            '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' => '-',
+            _ => c,
+        })
+        .collect();
+    folded.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+// Grammatical order in which form names are listed when one form fills several of them,
+// so "abandoned" reads "past tense, past participle". Names not listed here follow alphabetically.
+const FORM_ORDER: [&str; 9] = [
+    "past_tense",
+    "past_participle",
+    "present_participle",
+    "third_person_singular",
+    "plural",
+    "comparative",
+    "superlative",
+    "variant",
+    "synonym",
+];
+
+fn form_rank(kind: &str) -> (usize, &str) {
+    let position = FORM_ORDER.iter().position(|name| *name == kind).unwrap_or(FORM_ORDER.len());
+    (position, kind)
+}
+
+impl WordDefinition {
+    // Lowercased inflected forms mapped to the form names they appear under,
+    // e.g. "abandoned" -> ["past_tense", "past_participle"]. Values may be a string or a list of strings.
+    pub fn inflected_forms(&self) -> BTreeMap<String, Vec<String>> {
+        let mut result: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let headword = normalize_headword(&self.word);
+
+        for (kind, value) in self.forms.iter().flatten() {
+            let values: Vec<&str> = match value {
+                Value::String(form) => vec![form.as_str()],
+                Value::Array(items) => items.iter().filter_map(|item| item.as_str()).collect(),
+                _ => Vec::new(),
+            };
+
+            for form in values {
+                let form = normalize_headword(form);
+                if form.is_empty() || form == headword {
+                    continue;
+                }
+                let kinds = result.entry(form).or_default();
+                if !kinds.contains(kind) {
+                    kinds.push(kind.clone());
+                    kinds.sort_by(|a, b| form_rank(a).cmp(&form_rank(b)));
+                }
+            }
+        }
+
+        result
+    }
+
+    // How `form` relates to this entry, if it is one of its inflected forms
+    pub fn inflection_of(&self, form: &str) -> Option<Inflection> {
+        let form = normalize_headword(form);
+        let kinds = self.inflected_forms().remove(&form)?;
+        Some(Inflection {
+            form,
+            kinds,
+            lemma: self.word.clone(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Inflection {
+    pub form: String,
+    pub kinds: Vec<String>,
+    pub lemma: String,
+}
+
+impl Inflection {
+    // e.g. "abandoned → past tense, past participle of abandon"
+    pub fn describe(&self) -> String {
+        let kinds: Vec<String> = self.kinds.iter().map(|kind| kind.replace('_', " ")).collect();
+        format!("{} → {} of {}", self.form, kinds.join(", "), self.lemma)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Meaning {
     #[serde(rename = "pos")]
@@ -35,3 +126,98 @@ pub struct Comparison {
     pub word: String,
     pub analysis: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_headword_folds_case() {
+        assert_eq!(normalize_headword("Give Up"), "give up");
+        assert_eq!(normalize_headword("NASA"), "nasa");
+        // Non-ASCII letters are lowercased too
+        assert_eq!(normalize_headword("Éclair"), "éclair");
+        assert_eq!(normalize_headword("ΣΟΦΙΑ"), "σοφια");
+    }
+
+    #[test]
+    fn normalize_headword_collapses_whitespace() {
+        assert_eq!(normalize_headword("  give   up \t"), "give up");
+        assert_eq!(normalize_headword("give\nup"), "give up");
+        // No-break and ideographic spaces count as whitespace
+        assert_eq!(normalize_headword("give\u{00A0}up\u{3000}"), "give up");
+        assert_eq!(normalize_headword("   "), "");
+        assert_eq!(normalize_headword(""), "");
+    }
+
+    #[test]
+    fn normalize_headword_folds_typographic_punctuation() {
+        assert_eq!(normalize_headword("rock’n’roll"), "rock'n'roll");
+        assert_eq!(normalize_headword("‘tis"), "'tis");
+        assert_eq!(normalize_headword("o\u{02BC}clock"), "o'clock");
+        assert_eq!(normalize_headword("well‐known"), "well-known");
+        assert_eq!(normalize_headword("self–made"), "self-made");
+        // An em dash is not a hyphen and is kept
+        assert_eq!(normalize_headword("a—b"), "a—b");
+        // Chinese text is left alone
+        assert_eq!(normalize_headword(" 放弃 "), "放弃");
+    }
+
+    fn abandon() -> WordDefinition {
+        serde_json::from_value(serde_json::json!({
+            "word": "abandon",
+            "forms": {
+                "third_person_singular": "abandons",
+                "past_participle": "abandoned",
+                "past_tense": "Abandoned",
+                "present_participle": ["abandoning", "  abandoning "],
+                "plural": ["abandon"],
+                "note": 3
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn inflected_forms_are_normalized_and_keep_grammatical_order() {
+        let forms = abandon().inflected_forms();
+        assert_eq!(forms.keys().collect::<Vec<_>>(), ["abandoned", "abandoning", "abandons"]);
+        assert_eq!(forms["abandoned"], ["past_tense", "past_participle"]);
+        assert_eq!(forms["abandoning"], ["present_participle"]);
+        assert_eq!(forms["abandons"], ["third_person_singular"]);
+    }
+
+    #[test]
+    fn unknown_form_names_follow_known_ones() {
+        let definition: WordDefinition = serde_json::from_value(serde_json::json!({
+            "word": "colour",
+            "forms": { "zeta": "colours", "alpha": "colours", "plural": "colours" }
+        }))
+        .unwrap();
+        assert_eq!(definition.inflected_forms()["colours"], ["plural", "alpha", "zeta"]);
+    }
+
+    #[test]
+    fn inflection_of_resolves_forms_but_not_the_headword() {
+        let inflection = abandon().inflection_of("ABANDONED ").unwrap();
+        assert_eq!(inflection.form, "abandoned");
+        assert_eq!(inflection.lemma, "abandon");
+        assert_eq!(inflection.kinds, ["past_tense", "past_participle"]);
+
+        assert!(abandon().inflection_of("abandon").is_none());
+        assert!(abandon().inflection_of("abandonment").is_none());
+    }
+
+    #[test]
+    fn describe_lists_kinds_in_grammatical_order() {
+        let definition = abandon();
+        assert_eq!(
+            definition.inflection_of("abandoned").unwrap().describe(),
+            "abandoned → past tense, past participle of abandon"
+        );
+        assert_eq!(
+            definition.inflection_of("abandons").unwrap().describe(),
+            "abandons → third person singular of abandon"
+        );
+    }
+}
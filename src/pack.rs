@@ -0,0 +1,240 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use flate2::Compression;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::WordDefinition;
+use crate::scan;
+
+// 词典包：整个词典放在一个 JSONL 文件中，每行一个 WordDefinition，可以用 gzip 或 zstd 压缩。
+// 可以代替 words 目录作为词典的来源（words_dir 指向包文件），也可以与目录互相转换。
+
+// zstd 默认压缩级别
+const ZSTD_LEVEL: i32 = 19;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackCompression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl PackCompression {
+    // 按文件名识别：.jsonl、.jsonl.gz、.jsonl.zst；不是词典包时返回 None
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+        if name.ends_with(".jsonl") {
+            Some(PackCompression::None)
+        } else if name.ends_with(".jsonl.gz") {
+            Some(PackCompression::Gzip)
+        } else if name.ends_with(".jsonl.zst") || name.ends_with(".jsonl.zstd") {
+            Some(PackCompression::Zstd)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn is_pack(path: &Path) -> bool {
+    PackCompression::from_path(path).is_some()
+}
+
+// 打开词典包，按行读取解压后的内容
+pub fn open_pack(path: &Path) -> io::Result<Box<dyn BufRead + Send>> {
+    let file = File::open(path)?;
+    Ok(match PackCompression::from_path(path) {
+        Some(PackCompression::Gzip) => Box::new(BufReader::new(MultiGzDecoder::new(BufReader::new(file)))),
+        Some(PackCompression::Zstd) => Box::new(BufReader::new(zstd::Decoder::new(file)?)),
+        _ => Box::new(BufReader::new(file)),
+    })
+}
+
+// 写入词典包；压缩格式由文件名决定，必须调用 finish 才能写完压缩流
+pub enum PackWriter {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl PackWriter {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        Self::create_with(path, PackCompression::from_path(path).unwrap_or(PackCompression::None))
+    }
+
+    // 按指定的压缩格式写入，不看文件名（用于写临时文件）
+    pub fn create_with(path: &Path, compression: PackCompression) -> io::Result<Self> {
+        let file = BufWriter::new(File::create(path)?);
+        Ok(match compression {
+            PackCompression::Gzip => PackWriter::Gzip(GzEncoder::new(file, Compression::default())),
+            PackCompression::Zstd => PackWriter::Zstd(zstd::Encoder::new(file, ZSTD_LEVEL)?),
+            PackCompression::None => PackWriter::Plain(file),
+        })
+    }
+
+    pub fn finish(self) -> io::Result<()> {
+        let mut file = match self {
+            PackWriter::Plain(file) => file,
+            PackWriter::Gzip(encoder) => encoder.finish()?,
+            PackWriter::Zstd(encoder) => encoder.finish()?,
+        };
+        file.flush()
+    }
+}
+
+impl Write for PackWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            PackWriter::Plain(file) => file.write(buf),
+            PackWriter::Gzip(encoder) => encoder.write(buf),
+            PackWriter::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            PackWriter::Plain(file) => file.flush(),
+            PackWriter::Gzip(encoder) => encoder.flush(),
+            PackWriter::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PackSummary {
+    pub entries: usize,
+    // 无法解析为 WordDefinition 的文件，未写入词典包
+    pub errors: usize,
+}
+
+// words 目录（包括子目录）-> 词典包：按相对路径顺序，每个 JSON 文件压缩为一行；保留文件中的全部字段。
+// 先写到同目录下的临时文件，写完后再改名，出错时不会留下不完整的词典包，也不会破坏已有的包
+pub fn pack_directory(words_dir: &Path, pack_path: &Path) -> Result<PackSummary, Box<dyn Error>> {
+    let files = scan::word_files(words_dir)?;
+
+    let compression = PackCompression::from_path(pack_path).unwrap_or(PackCompression::None);
+    let file_name = pack_path.file_name().ok_or_else(|| format!("Invalid pack path: {}", pack_path.display()))?;
+    let tmp_path = pack_path.with_file_name(format!("{}.tmp", file_name.to_string_lossy()));
+    let writer = PackWriter::create_with(&tmp_path, compression)
+        .map_err(|e| format!("Failed to create {}: {}", tmp_path.display(), e))?;
+
+    let result = write_entries(files, writer).and_then(|summary| {
+        fs::rename(&tmp_path, pack_path).map_err(|e| format!("Failed to write {}: {}", pack_path.display(), e))?;
+        Ok(summary)
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+fn write_entries(files: Vec<scan::WordFile>, mut writer: PackWriter) -> Result<PackSummary, Box<dyn Error>> {
+    let mut summary = PackSummary::default();
+    for file in files {
+        let path = file.path;
+        let data = fs::read_to_string(&path)?;
+        let value: Value = match serde_json::from_str(&data) {
+            Ok(value) => value,
+            Err(e) => {
+                eprintln!("Warning: Failed to parse JSON file {:?}: {}", path, e);
+                summary.errors += 1;
+                continue;
+            }
+        };
+        if let Err(e) = WordDefinition::deserialize(&value) {
+            eprintln!("Warning: {:?} is not a word definition: {}", path, e);
+            summary.errors += 1;
+            continue;
+        }
+
+        serde_json::to_writer(&mut writer, &value)?;
+        writer.write_all(b"\n")?;
+        summary.entries += 1;
+    }
+    writer.finish()?;
+    Ok(summary)
+}
+
+// 读取词典包中的全部词条；有无法解析的行时返回错误（带行号）
+pub fn read_definitions(pack_path: &Path) -> Result<Vec<WordDefinition>, Box<dyn Error>> {
+    let reader = open_pack(pack_path).map_err(|e| format!("Failed to open {}: {}", pack_path.display(), e))?;
+    let mut definitions = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let definition = serde_json::from_str(&line)
+            .map_err(|e| format!("{}:{}: {}", pack_path.display(), number + 1, e))?;
+        definitions.push(definition);
+    }
+    Ok(definitions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words_dir(temp: &tempfile::TempDir) -> std::path::PathBuf {
+        let words_dir = temp.path().join("words");
+        fs::create_dir_all(words_dir.join("g")).unwrap();
+        fs::write(
+            words_dir.join("abandon.json"),
+            r#"{"word": "abandon", "concise_definition": "v. 放弃", "definitions": [{"pos": "verb", "explanation_en": "To leave, \"forever\"."}]}"#,
+        )
+        .unwrap();
+        fs::write(words_dir.join("g").join("give.json"), r#"{"word": "give", "forms": {"past_tense": "gave"}}"#).unwrap();
+        fs::write(words_dir.join("broken.json"), "{ not json").unwrap();
+        words_dir
+    }
+
+    #[test]
+    fn packs_round_trip_in_every_format() {
+        let temp = tempfile::tempdir().unwrap();
+        let words_dir = words_dir(&temp);
+        for name in ["words.jsonl", "words.jsonl.gz", "words.jsonl.zst"] {
+            let pack_path = temp.path().join(name);
+            let summary = pack_directory(&words_dir, &pack_path).unwrap();
+            assert_eq!((summary.entries, summary.errors), (2, 1), "{}", name);
+            assert!(!temp.path().join(format!("{}.tmp", name)).exists());
+
+            let definitions = read_definitions(&pack_path).unwrap();
+            let words: Vec<&str> = definitions.iter().map(|definition| definition.word.as_str()).collect();
+            assert_eq!(words, ["abandon", "give"], "{}", name);
+            assert_eq!(definitions[0].concise_definition.as_deref(), Some("v. 放弃"));
+            assert_eq!(definitions[0].meanings.as_ref().unwrap()[0].explanation_en, "To leave, \"forever\".");
+            assert_eq!(definitions[1].inflected_forms()["gave"], ["past_tense"]);
+        }
+
+        // 压缩格式确实不同
+        let plain = fs::read(temp.path().join("words.jsonl")).unwrap();
+        assert!(plain.starts_with(b"{"));
+        assert!(fs::read(temp.path().join("words.jsonl.gz")).unwrap().starts_with(&[0x1f, 0x8b]));
+        assert!(fs::read(temp.path().join("words.jsonl.zst")).unwrap().starts_with(&[0x28, 0xb5, 0x2f, 0xfd]));
+    }
+
+    #[test]
+    fn failed_pack_leaves_the_existing_pack_untouched() {
+        let temp = tempfile::tempdir().unwrap();
+        let words_dir = words_dir(&temp);
+        let pack_path = temp.path().join("words.jsonl.gz");
+        pack_directory(&words_dir, &pack_path).unwrap();
+        let before = fs::read(&pack_path).unwrap();
+
+        // 不是 UTF-8 的文件读取失败，打包中途出错
+        fs::write(words_dir.join("g").join("garbled.json"), [0xff, 0xfe, 0x00]).unwrap();
+        assert!(pack_directory(&words_dir, &pack_path).is_err());
+        assert_eq!(fs::read(&pack_path).unwrap(), before);
+        assert!(!temp.path().join("words.jsonl.gz.tmp").exists());
+
+        let new_pack = temp.path().join("new.jsonl.zst");
+        assert!(pack_directory(&words_dir, &new_pack).is_err());
+        assert!(!new_pack.exists());
+        assert!(!temp.path().join("new.jsonl.zst.tmp").exists());
+    }
+}
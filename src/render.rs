@@ -0,0 +1,97 @@
+use std::fmt::Write;
+
+use crate::models::{Inflection, WordDefinition};
+
+// 纯文本排版的词条，与图形界面中的顺序一致：词头、音标、简明释义、各义项、辨析
+pub fn plain_text(definition: &WordDefinition) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "{}", definition.word);
+    if let Some(phonetic) = definition.phonetic.as_deref().filter(|p| !p.is_empty()) {
+        let _ = writeln!(out, "/{}/", phonetic);
+    }
+    if let Some(concise) = definition.concise_definition.as_deref().filter(|c| !c.is_empty()) {
+        let _ = writeln!(out, "{}", concise);
+    }
+
+    for (i, meaning) in definition.meanings.iter().flatten().enumerate() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "{}. {}", i + 1, meaning.part_of_speech);
+        let _ = writeln!(out, "   {}", meaning.explanation_en);
+        if let Some(explanation_cn) = &meaning.explanation_cn {
+            let _ = writeln!(out, "   {}", explanation_cn);
+        }
+        if let Some(example_en) = &meaning.example_en {
+            let _ = writeln!(out, "     e.g. {}", example_en);
+        }
+        if let Some(example_cn) = &meaning.example_cn {
+            let _ = writeln!(out, "          {}", example_cn);
+        }
+    }
+
+    let comparisons = definition.comparisons.as_deref().unwrap_or_default();
+    if !comparisons.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "Compare:");
+        for comparison in comparisons {
+            let _ = writeln!(out, "  {}", comparison.word);
+            if let Some(analysis) = &comparison.analysis {
+                let _ = writeln!(out, "    {}", analysis);
+            }
+        }
+    }
+
+    out
+}
+
+// Markdown 排版的词条（批量导出单词表用）；inflection 为通过屈折形式查到时的说明
+pub fn markdown(definition: &WordDefinition, inflection: Option<&Inflection>) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "## {}", definition.word);
+    let _ = writeln!(out);
+    if let Some(inflection) = inflection {
+        let _ = writeln!(out, "*{}*", inflection.describe());
+        let _ = writeln!(out);
+    }
+    if let Some(phonetic) = definition.phonetic.as_deref().filter(|p| !p.is_empty()) {
+        let _ = writeln!(out, "/{}/", phonetic);
+        let _ = writeln!(out);
+    }
+    if let Some(concise) = definition.concise_definition.as_deref().filter(|c| !c.is_empty()) {
+        let _ = writeln!(out, "**{}**", concise);
+        let _ = writeln!(out);
+    }
+
+    for (i, meaning) in definition.meanings.iter().flatten().enumerate() {
+        let _ = writeln!(out, "{}. *{}* {}", i + 1, meaning.part_of_speech, meaning.explanation_en);
+        if let Some(explanation_cn) = &meaning.explanation_cn {
+            let _ = writeln!(out, "   {}", explanation_cn);
+        }
+        if let Some(example_en) = &meaning.example_en {
+            let _ = writeln!(out, "   > {}", example_en);
+            if let Some(example_cn) = &meaning.example_cn {
+                let _ = writeln!(out, "   > {}", example_cn);
+            }
+        }
+    }
+
+    let comparisons = definition.comparisons.as_deref().unwrap_or_default();
+    if !comparisons.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "**Compare:**");
+        let _ = writeln!(out);
+        for comparison in comparisons {
+            match &comparison.analysis {
+                Some(analysis) => {
+                    let _ = writeln!(out, "- **{}**: {}", comparison.word, analysis);
+                }
+                None => {
+                    let _ = writeln!(out, "- **{}**", comparison.word);
+                }
+            }
+        }
+    }
+
+    out
+}
@@ -0,0 +1,199 @@
+use std::path::{Component, Path, PathBuf};
+
+use ignore::gitignore::Gitignore;
+use ignore::{Match, WalkBuilder};
+
+// words 目录的遍历：词条文件可以放在任意层级的子目录中（例如按首字母分目录 words/a/abandon.json），
+// 忽略隐藏文件和目录（包括 .index 及其 .index.building、.index.old）、其他 tantivy 索引目录，
+// 以及忽略文件中列出的路径。忽略文件的语法与 .gitignore 相同，可以放在任意一级目录中。
+
+pub const IGNORE_FILE: &str = ".dictnaviignore";
+
+// 顶层目录在按目录统计中的名称
+pub const ROOT_DIRECTORY: &str = ".";
+
+// tantivy 索引目录中总会有的文件，用来识别放在 words 目录中的自定义索引目录
+const TANTIVY_MANAGED_FILE: &str = ".managed.json";
+
+#[derive(Debug, Clone)]
+pub struct WordFile {
+    // 相对 words 目录的路径，用 "/" 分隔，作为清单中的键
+    pub rel_path: String,
+    pub path: PathBuf,
+}
+
+// 递归列出 words 目录中的词条文件，按相对路径排序
+pub fn word_files(words_dir: &Path) -> Result<Vec<WordFile>, String> {
+    if !words_dir.is_dir() {
+        return Err(format!("Failed to read words directory: {} is not a directory", words_dir.display()));
+    }
+
+    let walker = WalkBuilder::new(words_dir)
+        .standard_filters(false)
+        .hidden(true)
+        .add_custom_ignore_filename(IGNORE_FILE)
+        .filter_entry(|entry| {
+            !entry.file_type().is_some_and(|t| t.is_dir()) || !entry.path().join(TANTIVY_MANAGED_FILE).exists()
+        })
+        .build();
+
+    let mut files = Vec::new();
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                eprintln!("Warning: Failed to read words directory entry: {}", e);
+                continue;
+            }
+        };
+        if !entry.file_type().is_some_and(|t| t.is_file()) || !is_word_file(entry.path()) {
+            continue;
+        }
+        if let Some(rel_path) = rel_path(words_dir, entry.path()) {
+            files.push(WordFile {
+                rel_path,
+                path: entry.into_path(),
+            });
+        }
+    }
+    files.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+    Ok(files)
+}
+
+// 词条文件：扩展名为 .json
+pub fn is_word_file(path: &Path) -> bool {
+    path.extension().map(|s| s == "json").unwrap_or(false)
+}
+
+// 相对 words 目录的路径（"/" 分隔）；不在 words 目录中时返回 None
+pub fn rel_path(words_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(words_dir).ok()?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+// 词条文件所在的目录（相对 words 目录），顶层为 "."
+pub fn directory_of(rel_path: &str) -> &str {
+    rel_path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or(ROOT_DIRECTORY)
+}
+
+// 判断单个路径是否被忽略（文件监视器收到的变化不经过目录遍历）：
+// 路径中有隐藏的部分，或从 words 目录逐级向下的忽略文件中最后一条匹配的规则是忽略
+pub fn is_ignored(words_dir: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(words_dir) else {
+        return true;
+    };
+    if relative.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.')) {
+        return true;
+    }
+
+    let is_dir = path.is_dir();
+    let mut dir = words_dir.to_path_buf();
+    let mut ignored = false;
+    for component in relative.components() {
+        let ignore_file = dir.join(IGNORE_FILE);
+        if ignore_file.is_file() {
+            let (matcher, error) = Gitignore::new(&ignore_file);
+            if let Some(e) = error {
+                eprintln!("Warning: Failed to parse {:?}: {}", ignore_file, e);
+            }
+            match matcher.matched_path_or_any_parents(path, is_dir) {
+                Match::Ignore(_) => ignored = true,
+                Match::Whitelist(_) => ignored = false,
+                Match::None => {}
+            }
+        }
+        dir.push(component);
+        if dir.join(TANTIVY_MANAGED_FILE).exists() {
+            return true;
+        }
+    }
+    ignored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::Dictionary;
+    use crate::manifest::IndexManifest;
+    use std::fs;
+
+    fn write_word(words_dir: &Path, rel_path: &str) {
+        let path = words_dir.join(rel_path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let word = path.file_stem().unwrap().to_string_lossy().into_owned();
+        fs::write(path, serde_json::json!({ "word": word }).to_string()).unwrap();
+    }
+
+    // 顶层忽略文件排除 drafts/ 和 *.bak.json，子目录中的忽略文件再排除 old_*.json 并重新包含 old_keep.json；
+    // 隐藏文件、隐藏目录和放在 words 目录中的 .index 也不参与
+    fn words_dir(temp: &tempfile::TempDir) -> PathBuf {
+        let words_dir = temp.path().join("words");
+        for rel_path in [
+            "abandon.json",
+            "a/ability.json",
+            "a/old_able.json",
+            "a/old_keep.json",
+            "a/.hidden.json",
+            ".drafts/secret.json",
+            "drafts/zebra.json",
+            "give.bak.json",
+        ] {
+            write_word(&words_dir, rel_path);
+        }
+        fs::write(words_dir.join("notes.txt"), "not a word").unwrap();
+        fs::write(words_dir.join(IGNORE_FILE), "# 草稿\ndrafts/\n*.bak.json\n").unwrap();
+        fs::write(words_dir.join("a").join(IGNORE_FILE), "old_*.json\n!old_keep.json\n").unwrap();
+        words_dir
+    }
+
+    const EXPECTED: [&str; 3] = ["a/ability.json", "a/old_keep.json", "abandon.json"];
+
+    #[test]
+    fn scan_skips_ignored_and_hidden_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let words_dir = words_dir(&temp);
+        let files = word_files(&words_dir).unwrap();
+        let rel_paths: Vec<&str> = files.iter().map(|file| file.rel_path.as_str()).collect();
+        assert_eq!(rel_paths, EXPECTED);
+        assert_eq!(files[0].path, words_dir.join("a").join("ability.json"));
+
+        // 单个路径的判断与目录遍历一致
+        for rel_path in ["a/old_able.json", "a/.hidden.json", ".drafts/secret.json", "drafts/zebra.json", "give.bak.json"] {
+            assert!(is_ignored(&words_dir, &words_dir.join(rel_path)), "{}", rel_path);
+        }
+        for rel_path in EXPECTED {
+            assert!(!is_ignored(&words_dir, &words_dir.join(rel_path)), "{}", rel_path);
+        }
+        assert!(is_ignored(&words_dir, &temp.path().join("outside.json")));
+    }
+
+    #[test]
+    fn manifest_records_only_scanned_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let words_dir = words_dir(&temp);
+        let index_dir = words_dir.join(".index");
+        let dictionary = Dictionary::with_index_path(
+            words_dir.to_string_lossy().into_owned(),
+            index_dir.to_string_lossy().into_owned(),
+        );
+        let summary = tokio::runtime::Runtime::new().unwrap().block_on(dictionary.rebuild_index_async()).unwrap();
+        assert_eq!((summary.total_files, summary.errors), (EXPECTED.len(), 0));
+
+        // 索引建好后 .index 在 words 目录中，仍然不会被当作词条
+        assert_eq!(word_files(&words_dir).unwrap().len(), EXPECTED.len());
+
+        let manifest = IndexManifest::load(&index_dir.to_string_lossy()).unwrap();
+        let recorded: Vec<&str> = manifest.entries.keys().map(String::as_str).collect();
+        assert_eq!(recorded, EXPECTED);
+        assert!(manifest.failed.is_empty());
+        assert!(dictionary.lookup_word("zebra").unwrap().is_none());
+        assert!(dictionary.lookup_word("old_keep").unwrap().is_some());
+    }
+}
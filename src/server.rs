@@ -0,0 +1,237 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::dictionary::{FullTextHit, SyncSummary};
+use crate::dictionary_set::DictionarySet;
+use crate::models::WordDefinition;
+
+const DEFAULT_SUGGEST_LIMIT: usize = 10;
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+// 单次请求最多返回的结果数
+const MAX_LIMIT: usize = 200;
+// 查词结果所在词典的名称
+const SOURCE_HEADER: &str = "x-dictionary-source";
+
+// HTTP 接口的错误：状态码和 {"error": ...} 响应体
+pub struct ApiError {
+    status: StatusCode,
+    body: serde_json::Value,
+}
+
+impl ApiError {
+    fn internal(message: impl std::fmt::Display) -> Self {
+        ApiError {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            body: json!({ "error": message.to_string() }),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body)).into_response()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SuggestParams {
+    prefix: String,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct SearchParams {
+    q: String,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct ReindexParams {
+    // 为 true 时全量重建，否则增量同步
+    #[serde(default)]
+    full: bool,
+}
+
+#[derive(Serialize)]
+pub struct Suggestion {
+    word: String,
+    concise_definition: String,
+}
+
+// 路由：查询接口只读，/admin/reindex 与图形界面的“同步索引”相同
+pub fn router(dictionary: Arc<DictionarySet>) -> Router {
+    Router::new()
+        .route("/lookup/{word}", get(lookup))
+        .route("/suggest", get(suggest))
+        .route("/search", get(search))
+        .route("/admin/reindex", post(reindex))
+        .with_state(dictionary)
+}
+
+// 在 addr 上监听，直到进程退出
+pub async fn serve(dictionary: Arc<DictionarySet>, addr: SocketAddr) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    eprintln!("DictNavi HTTP API listening on http://{}", listener.local_addr()?);
+    axum::serve(listener, router(dictionary)).await
+}
+
+// 索引查询是阻塞操作，放到线程池执行，避免阻塞其他请求
+async fn blocking<T, F>(dictionary: &Arc<DictionarySet>, f: F) -> Result<T, ApiError>
+where
+    T: Send + 'static,
+    F: FnOnce(&DictionarySet) -> Result<T, Box<dyn std::error::Error>> + Send + 'static,
+{
+    let dictionary = Arc::clone(dictionary);
+    tokio::task::spawn_blocking(move || f(&dictionary).map_err(|e| e.to_string()))
+        .await
+        .map_err(ApiError::internal)?
+        .map_err(ApiError::internal)
+}
+
+async fn lookup(State(dictionary): State<Arc<DictionarySet>>, Path(word): Path<String>) -> Result<([(&'static str, String); 1], Json<WordDefinition>), ApiError> {
+    let query = word.clone();
+    let (result, suggestions) = blocking(&dictionary, move |dictionary| {
+        match dictionary.lookup(&query)? {
+            Some(result) => Ok((Some(result), Vec::new())),
+            None => Ok((None, dictionary.suggest_similar(&query, 5)?)),
+        }
+    })
+    .await?;
+
+    result
+        .map(|result| ([(SOURCE_HEADER, result.source)], Json(result.definition)))
+        .ok_or(ApiError {
+            status: StatusCode::NOT_FOUND,
+            body: json!({ "error": "not found", "word": word, "suggestions": suggestions }),
+        })
+}
+
+async fn suggest(State(dictionary): State<Arc<DictionarySet>>, Query(params): Query<SuggestParams>) -> Result<Json<Vec<Suggestion>>, ApiError> {
+    let limit = params.limit.unwrap_or(DEFAULT_SUGGEST_LIMIT).min(MAX_LIMIT);
+    let results = blocking(&dictionary, move |dictionary| dictionary.search_words(&params.prefix, limit)).await?;
+
+    Ok(Json(
+        results
+            .into_iter()
+            .map(|(word, concise_definition)| Suggestion { word, concise_definition })
+            .collect(),
+    ))
+}
+
+async fn search(State(dictionary): State<Arc<DictionarySet>>, Query(params): Query<SearchParams>) -> Result<Json<Vec<FullTextHit>>, ApiError> {
+    let limit = params.limit.unwrap_or(DEFAULT_SEARCH_LIMIT).min(MAX_LIMIT);
+    let hits = blocking(&dictionary, move |dictionary| dictionary.full_text_search(&params.q, limit)).await?;
+    Ok(Json(hits))
+}
+
+async fn reindex(State(dictionary): State<Arc<DictionarySet>>, Query(params): Query<ReindexParams>) -> Result<Json<SyncSummary>, ApiError> {
+    let summary = if params.full {
+        dictionary.rebuild_index_async().await
+    } else {
+        dictionary.sync_index_async().await
+    };
+    summary.map(Json).map_err(ApiError::internal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::Dictionary;
+    use axum::body::Body;
+    use axum::http::Request;
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    fn dictionary(temp: &tempfile::TempDir) -> Arc<DictionarySet> {
+        let words_dir = temp.path().join("words");
+        std::fs::create_dir_all(&words_dir).unwrap();
+        let abandon = json!({
+            "word": "abandon",
+            "concise_definition": "v. 放弃",
+            "forms": { "past_tense": "abandoned" },
+            "definitions": [{ "pos": "verb", "explanation_en": "To leave a ship or place forever." }]
+        });
+        std::fs::write(words_dir.join("abandon.json"), abandon.to_string()).unwrap();
+        let dictionary = Dictionary::with_index_path(
+            words_dir.to_string_lossy().into_owned(),
+            temp.path().join("index").to_string_lossy().into_owned(),
+        );
+        let set = DictionarySet::single(dictionary);
+        tokio::runtime::Runtime::new().unwrap().block_on(set.rebuild_index_async()).unwrap();
+        Arc::new(set)
+    }
+
+    // 发送一个 GET 请求，返回状态码、来源响应头和 JSON 响应体
+    fn get_json(dictionary: Arc<DictionarySet>, uri: &str) -> (StatusCode, Option<String>, serde_json::Value) {
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let request = Request::get(uri).body(Body::empty()).unwrap();
+            let response = router(dictionary).oneshot(request).await.unwrap();
+            let status = response.status();
+            let source = response
+                .headers()
+                .get(SOURCE_HEADER)
+                .map(|value| value.to_str().unwrap().to_string());
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            (status, source, serde_json::from_slice(&body).unwrap())
+        })
+    }
+
+    #[test]
+    fn lookup_returns_the_entry() {
+        let temp = tempfile::tempdir().unwrap();
+        let dictionary = dictionary(&temp);
+
+        let (status, source, body) = get_json(Arc::clone(&dictionary), "/lookup/abandon");
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(source.as_deref(), Some("default"));
+        assert_eq!(body["word"], "abandon");
+        assert_eq!(body["concise_definition"], "v. 放弃");
+
+        // 屈折形式查到原形
+        let (status, _, body) = get_json(dictionary, "/lookup/Abandoned");
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["word"], "abandon");
+    }
+
+    #[test]
+    fn lookup_of_a_missing_word_is_404_with_suggestions() {
+        let temp = tempfile::tempdir().unwrap();
+        let (status, source, body) = get_json(dictionary(&temp), "/lookup/abandom");
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(source, None);
+        assert_eq!(body, json!({ "error": "not found", "word": "abandom", "suggestions": ["abandon"] }));
+    }
+
+    #[test]
+    fn search_returns_full_text_hits() {
+        let temp = tempfile::tempdir().unwrap();
+        let dictionary = dictionary(&temp);
+
+        let (status, _, body) = get_json(Arc::clone(&dictionary), "/search?q=ship&limit=5");
+        assert_eq!(status, StatusCode::OK);
+        let hits = body.as_array().unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0]["word"], "abandon");
+        assert_eq!(hits[0]["field"], "explanation");
+        assert_eq!(hits[0]["source"], "default");
+
+        let (status, _, body) = get_json(Arc::clone(&dictionary), "/search?q=zebra");
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!([]));
+
+        // 缺少 q 参数
+        let status = tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let request = Request::get("/search").body(Body::empty()).unwrap();
+            router(dictionary).oneshot(request).await.unwrap().status()
+        });
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use serde_json::Value;
+
+use crate::import;
+use crate::models::WordDefinition;
+
+// StarDict 词典（.ifo/.idx/.dict.dz，可选 .syn）的读取：
+// .ifo 为 key=value 的说明，.idx 为按词头排序的 (词头\0, 偏移, 长度)，.dict 为释义数据（.dz 为 dictzip，即 gzip）
+
+// .syn 中的同义词写入 forms 的这个键，查同义词时通过屈折形式的机制找到原词条
+const SYNONYM_FORM: &str = "synonym";
+
+#[derive(Debug, Clone, Default)]
+pub struct StarDictInfo {
+    pub version: String,
+    pub bookname: String,
+    pub wordcount: usize,
+    pub synwordcount: usize,
+    // 3.0.0 起 .idx 中的偏移可以是 64 位
+    pub idxoffsetbits: u32,
+    // 每个词条的数据类型序列；为空时每个字段前带类型字符
+    pub sametypesequence: String,
+}
+
+impl StarDictInfo {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let mut lines = text.lines();
+        if lines.next().map(|line| line.trim_start_matches('\u{feff}').trim()) != Some("StarDict's dict ifo file") {
+            return Err(format!("{} is not a StarDict .ifo file", path.display()).into());
+        }
+
+        let mut info = StarDictInfo {
+            idxoffsetbits: 32,
+            ..Default::default()
+        };
+        for line in lines {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "version" => info.version = value.to_string(),
+                "bookname" => info.bookname = value.to_string(),
+                "wordcount" => info.wordcount = value.parse().unwrap_or(0),
+                "synwordcount" => info.synwordcount = value.parse().unwrap_or(0),
+                "idxoffsetbits" => info.idxoffsetbits = value.parse().unwrap_or(32),
+                "sametypesequence" => info.sametypesequence = value.to_string(),
+                _ => {}
+            }
+        }
+
+        if info.idxoffsetbits != 32 && info.idxoffsetbits != 64 {
+            return Err(format!("Unsupported idxoffsetbits {} in {}", info.idxoffsetbits, path.display()).into());
+        }
+        Ok(info)
+    }
+}
+
+// .idx 中的一项
+struct IndexEntry {
+    word: String,
+    offset: usize,
+    size: usize,
+}
+
+// 读取 StarDict 词典中的全部词条并转换为 WordDefinition；path 为 .ifo 文件或包含它的目录
+pub fn read_definitions(path: &Path) -> Result<Vec<WordDefinition>, Box<dyn Error>> {
+    let ifo_path = find_ifo(path)?;
+    let info = StarDictInfo::load(&ifo_path)?;
+    let base = ifo_path.with_extension("");
+
+    let idx = read_maybe_gzipped(&base, &["idx", "idx.gz"])?;
+    let entries = parse_idx(&idx, info.idxoffsetbits)?;
+    let dict = read_maybe_gzipped(&base, &["dict.dz", "dict"])?;
+    let synonyms = match read_maybe_gzipped(&base, &["syn"]) {
+        Ok(syn) => parse_syn(&syn, entries.len())?,
+        Err(_) => HashMap::new(),
+    };
+
+    if info.wordcount != 0 && info.wordcount != entries.len() {
+        eprintln!(
+            "Warning: {} declares {} words but the index has {}",
+            ifo_path.display(),
+            info.wordcount,
+            entries.len()
+        );
+    }
+
+    let mut definitions = Vec::with_capacity(entries.len());
+    for (i, entry) in entries.iter().enumerate() {
+        let data = entry_data(&dict, entry)?;
+        let (text, phonetic) = entry_text(data, &info.sametypesequence)
+            .map_err(|e| format!("Entry '{}': {}", entry.word, e))?;
+
+        let mut definition = import::definition_from_text(&entry.word, &text, phonetic);
+        if let Some(words) = synonyms.get(&i) {
+            let words: Vec<Value> = words.iter().map(|w| Value::String(w.clone())).collect();
+            definition.forms = Some(HashMap::from([(SYNONYM_FORM.to_string(), Value::Array(words))]));
+        }
+        definitions.push(definition);
+    }
+    Ok(definitions)
+}
+
+// 词条在 .dict 中的数据；偏移和长度来自 .idx 文件，不可信，相加溢出或越界时报告词条损坏
+fn entry_data<'a>(dict: &'a [u8], entry: &IndexEntry) -> Result<&'a [u8], String> {
+    entry
+        .offset
+        .checked_add(entry.size)
+        .and_then(|end| dict.get(entry.offset..end))
+        .ok_or_else(|| format!("Malformed entry '{}': it points outside the .dict file", entry.word))
+}
+
+fn find_ifo(path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    if !path.is_dir() {
+        return Ok(path.to_path_buf());
+    }
+    let mut ifo_files: Vec<PathBuf> = fs::read_dir(path)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|p| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("ifo")))
+        .collect();
+    match ifo_files.len() {
+        1 => Ok(ifo_files.remove(0)),
+        0 => Err(format!("No .ifo file in {}", path.display()).into()),
+        _ => Err(format!("More than one .ifo file in {}, pass the one to import", path.display()).into()),
+    }
+}
+
+// 依次尝试 base.<extension>，以 .gz/.dz 结尾的按 gzip 解压
+fn read_maybe_gzipped(base: &Path, extensions: &[&str]) -> Result<Vec<u8>, Box<dyn Error>> {
+    for extension in extensions {
+        let path = PathBuf::from(format!("{}.{}", base.display(), extension));
+        if !path.is_file() {
+            continue;
+        }
+        let data = fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if extension.ends_with(".gz") || extension.ends_with(".dz") {
+            let mut decompressed = Vec::new();
+            GzDecoder::new(data.as_slice())
+                .read_to_end(&mut decompressed)
+                .map_err(|e| format!("Failed to decompress {}: {}", path.display(), e))?;
+            return Ok(decompressed);
+        }
+        return Ok(data);
+    }
+    Err(format!("Missing {}.{}", base.display(), extensions[0]).into())
+}
+
+fn parse_idx(data: &[u8], offset_bits: u32) -> Result<Vec<IndexEntry>, Box<dyn Error>> {
+    let offset_size = (offset_bits / 8) as usize;
+    let mut entries = Vec::new();
+    let mut position = 0;
+
+    while position < data.len() {
+        let end = data[position..]
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or("Truncated .idx file: headword is not terminated")?;
+        let word = String::from_utf8_lossy(&data[position..position + end]).into_owned();
+        position += end + 1;
+
+        let fields = data
+            .get(position..position + offset_size + 4)
+            .ok_or("Truncated .idx file: missing offset or size")?;
+        let offset = if offset_size == 8 {
+            usize::try_from(u64::from_be_bytes(fields[..8].try_into()?))
+                .map_err(|_| format!("Malformed .idx entry '{}': offset does not fit in memory", word))?
+        } else {
+            u32::from_be_bytes(fields[..4].try_into()?) as usize
+        };
+        let size = u32::from_be_bytes(fields[offset_size..].try_into()?) as usize;
+        position += offset_size + 4;
+
+        entries.push(IndexEntry { word, offset, size });
+    }
+    Ok(entries)
+}
+
+// .syn：(同义词\0, 词条在 .idx 中的序号)，返回 序号 -> 同义词
+fn parse_syn(data: &[u8], entry_count: usize) -> Result<HashMap<usize, Vec<String>>, Box<dyn Error>> {
+    let mut synonyms: HashMap<usize, Vec<String>> = HashMap::new();
+    let mut position = 0;
+
+    while position < data.len() {
+        let end = data[position..]
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or("Truncated .syn file: word is not terminated")?;
+        let word = String::from_utf8_lossy(&data[position..position + end]).into_owned();
+        position += end + 1;
+
+        let index = data
+            .get(position..position + 4)
+            .ok_or("Truncated .syn file: missing index")?;
+        let index = u32::from_be_bytes(index.try_into()?) as usize;
+        position += 4;
+
+        if index < entry_count {
+            synonyms.entry(index).or_default().push(word);
+        }
+    }
+    Ok(synonyms)
+}
+
+// 取出词条数据中的文本字段，返回 (释义纯文本, 音标)。
+// 小写类型为以 \0 结尾的字符串，大写类型为 4 字节长度加数据；使用 sametypesequence 时最后一个字段占满剩余数据。
+// 图片、声音等非文本字段被忽略。
+fn entry_text(data: &[u8], sametypesequence: &str) -> Result<(String, Option<String>), Box<dyn Error>> {
+    let mut fields: Vec<(char, &[u8])> = Vec::new();
+    let mut position = 0;
+
+    if sametypesequence.is_empty() {
+        while position < data.len() {
+            let field_type = data[position] as char;
+            position += 1;
+            let (field, next) = read_field(data, position, field_type, false)?;
+            fields.push((field_type, field));
+            position = next;
+        }
+    } else {
+        let types: Vec<char> = sametypesequence.chars().collect();
+        for (i, &field_type) in types.iter().enumerate() {
+            let (field, next) = read_field(data, position, field_type, i == types.len() - 1)?;
+            fields.push((field_type, field));
+            position = next;
+        }
+    }
+
+    let mut texts = Vec::new();
+    let mut phonetic = None;
+    for (field_type, field) in fields {
+        let value = String::from_utf8_lossy(field);
+        match field_type {
+            'm' | 'l' => texts.push(value.into_owned()),
+            'g' | 'h' | 'x' => texts.push(import::strip_html(&value)),
+            't' | 'y' if phonetic.is_none() => phonetic = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+    Ok((texts.join("\n"), phonetic))
+}
+
+fn read_field(data: &[u8], position: usize, field_type: char, last: bool) -> Result<(&[u8], usize), Box<dyn Error>> {
+    if last {
+        return Ok((data.get(position..).unwrap_or_default(), data.len()));
+    }
+    if field_type.is_ascii_lowercase() {
+        let end = data[position..]
+            .iter()
+            .position(|&b| b == 0)
+            .map(|end| position + end)
+            .unwrap_or(data.len());
+        Ok((&data[position..end], (end + 1).min(data.len())))
+    } else {
+        let size = data
+            .get(position..position + 4)
+            .ok_or_else(|| format!("truncated '{}' field", field_type))?;
+        let size = u32::from_be_bytes(size.try_into()?) as usize;
+        let start = position + 4;
+        let field = start
+            .checked_add(size)
+            .and_then(|end| data.get(start..end))
+            .ok_or_else(|| format!("truncated '{}' field", field_type))?;
+        Ok((field, start + size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // .idx 中的一项：词头\0、偏移（32 或 64 位）、长度（32 位），都是大端
+    fn idx_entry(word: &str, offset: u64, size: u32, offset_bits: u32) -> Vec<u8> {
+        let mut out = word.as_bytes().to_vec();
+        out.push(0);
+        if offset_bits == 64 {
+            out.extend_from_slice(&offset.to_be_bytes());
+        } else {
+            out.extend_from_slice(&(offset as u32).to_be_bytes());
+        }
+        out.extend_from_slice(&size.to_be_bytes());
+        out
+    }
+
+    fn entries(parsed: &[IndexEntry]) -> Vec<(&str, usize, usize)> {
+        parsed.iter().map(|entry| (entry.word.as_str(), entry.offset, entry.size)).collect()
+    }
+
+    #[test]
+    fn parses_idx_with_32_bit_offsets() {
+        let mut data = idx_entry("apple", 0, 12, 32);
+        data.extend(idx_entry("苹果", 12, 300, 32));
+        let parsed = parse_idx(&data, 32).unwrap();
+        assert_eq!(entries(&parsed), [("apple", 0, 12), ("苹果", 12, 300)]);
+    }
+
+    #[test]
+    fn parses_idx_with_64_bit_offsets() {
+        let large = u32::MAX as u64 + 10;
+        let mut data = idx_entry("apple", 0, 12, 64);
+        data.extend(idx_entry("banana", large, 7, 64));
+        let parsed = parse_idx(&data, 64).unwrap();
+        assert_eq!(entries(&parsed), [("apple", 0, 12), ("banana", large as usize, 7)]);
+    }
+
+    #[test]
+    fn rejects_truncated_idx() {
+        let data = idx_entry("apple", 0, 12, 32);
+        assert!(parse_idx(&data[..data.len() - 1], 32).is_err());
+        assert!(parse_idx(b"apple", 32).is_err());
+    }
+
+    #[test]
+    fn rejects_entries_outside_the_dict_file() {
+        let dict = b"0123456789";
+        let entry = |offset, size| IndexEntry { word: "apple".to_string(), offset, size };
+        assert_eq!(entry_data(dict, &entry(2, 3)).unwrap(), b"234");
+        assert_eq!(entry_data(dict, &entry(10, 0)).unwrap(), b"");
+        assert!(entry_data(dict, &entry(8, 3)).is_err());
+        // 偏移加长度溢出 usize 时报告错误而不是 panic
+        assert!(entry_data(dict, &entry(usize::MAX - 1, 5)).is_err());
+        assert!(entry_data(dict, &entry(usize::MAX, usize::MAX)).is_err());
+    }
+
+    #[test]
+    fn parses_syn() {
+        let mut data = Vec::new();
+        for (word, index) in [("colour", 1u32), ("hue", 1), ("ghost", 9)] {
+            data.extend_from_slice(word.as_bytes());
+            data.push(0);
+            data.extend_from_slice(&index.to_be_bytes());
+        }
+        let synonyms = parse_syn(&data, 2).unwrap();
+        assert_eq!(synonyms.len(), 1);
+        assert_eq!(synonyms[&1], ["colour", "hue"]);
+    }
+
+    #[test]
+    fn entry_text_with_sametypesequence() {
+        // 最后一个字段占满剩余数据，不需要结束符
+        let (text, phonetic) = entry_text(b"'apl\0a round fruit", "tm").unwrap();
+        assert_eq!(text, "a round fruit");
+        assert_eq!(phonetic.as_deref(), Some("'apl"));
+
+        let (text, phonetic) = entry_text(b"<b>fruit</b>", "h").unwrap();
+        assert_eq!(text, "fruit");
+        assert_eq!(phonetic, None);
+    }
+
+    #[test]
+    fn entry_text_with_field_types() {
+        // 每个字段前有类型；大写类型为 4 字节长度加数据（这里是被忽略的图片）
+        let mut data = b"mfirst sense\0".to_vec();
+        data.push(b'P');
+        data.extend_from_slice(&3u32.to_be_bytes());
+        data.extend_from_slice(&[1, 2, 3]);
+        data.extend_from_slice(b"lsecond sense\0");
+        let (text, phonetic) = entry_text(&data, "").unwrap();
+        assert_eq!(text, "first sense\nsecond sense");
+        assert_eq!(phonetic, None);
+    }
+
+    #[test]
+    fn rejects_truncated_binary_field() {
+        let mut data = vec![b'P'];
+        data.extend_from_slice(&10u32.to_be_bytes());
+        data.extend_from_slice(&[1, 2]);
+        assert!(entry_text(&data, "").is_err());
+    }
+}
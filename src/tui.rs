@@ -0,0 +1,413 @@
+use crate::cjk;
+use crate::dictionary::LookupResult;
+use crate::dictionary_set::DictionarySet;
+use crate::models::{Inflection, WordDefinition};
+use crate::watcher::WordsWatcher;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Position};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// Same colors as the GUI: dark green for Chinese text, blue for parts of speech
+const CHINESE: Color = Color::Green;
+const PART_OF_SPEECH: Color = Color::Blue;
+
+// How many entries the recent history keeps
+const HISTORY_LIMIT: usize = 50;
+
+// What the side list currently shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListMode {
+    Suggestions,
+    Reverse,
+    DidYouMean,
+    History,
+}
+
+impl ListMode {
+    fn title(self) -> &'static str {
+        match self {
+            ListMode::Suggestions => " Suggestions ",
+            ListMode::Reverse => " English words ",
+            ListMode::DidYouMean => " Did you mean ",
+            ListMode::History => " Recent ",
+        }
+    }
+}
+
+// Terminal counterpart of `DictNaviApp`: search box with live suggestions, the entry on the right,
+// and the recent history when the search box is empty
+pub struct DictNaviTui {
+    dictionary: Arc<DictionarySet>,
+    search_term: String,
+    cursor: usize, // Cursor position in characters
+    results: Vec<LookupResult>, // Best match first, then the same word in other dictionaries
+    message: Option<String>,
+    list: Vec<(String, String)>, // (word, concise definition)
+    list_mode: ListMode,
+    list_state: ListState,
+    search_history: Vec<String>,
+    scroll: u16,
+    should_quit: bool,
+    _words_watchers: Vec<WordsWatcher>, // One per dictionary
+    watcher_changes: Arc<Mutex<Vec<String>>>,
+}
+
+impl DictNaviTui {
+    pub fn new(dictionary: Arc<DictionarySet>) -> Self {
+        let watcher_changes = Arc::new(Mutex::new(Vec::new()));
+        let words_watchers = dictionary
+            .sources()
+            .iter()
+            .filter_map(|source| {
+                let changes = Arc::clone(&watcher_changes);
+                WordsWatcher::start(Arc::clone(source), move |result| {
+                    if let Ok(summary) = result {
+                        changes.lock().unwrap().extend(summary.changed_words);
+                    }
+                })
+                .ok()
+            })
+            .collect();
+
+        Self {
+            dictionary,
+            search_term: String::new(),
+            cursor: 0,
+            results: Vec::new(),
+            message: None,
+            list: Vec::new(),
+            list_mode: ListMode::History,
+            list_state: ListState::default(),
+            search_history: Vec::new(),
+            scroll: 0,
+            should_quit: false,
+            _words_watchers: words_watchers,
+            watcher_changes,
+        }
+    }
+
+    // Run the event loop until the user quits; the terminal is restored on return
+    pub fn run(mut self) -> std::io::Result<()> {
+        let mut terminal = ratatui::init();
+        let result = self.event_loop(&mut terminal);
+        ratatui::restore();
+        result
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        self.update_list();
+        while !self.should_quit {
+            self.apply_watcher_changes();
+            terminal.draw(|frame| self.draw(frame))?;
+
+            // Poll so that entries reindexed by the watcher show up without a key press
+            if event::poll(Duration::from_millis(250))?
+                && let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                self.handle_key(key);
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c') | KeyCode::Char('q') if ctrl => self.should_quit = true,
+            KeyCode::Esc => {
+                if self.search_term.is_empty() {
+                    self.should_quit = true;
+                } else {
+                    self.clear_search();
+                }
+            }
+            KeyCode::Char('u') if ctrl => self.clear_search(),
+            KeyCode::Enter => self.search_word(),
+            KeyCode::Tab => {
+                // Complete the search box with the selected (or first) suggestion
+                let index = self.list_state.selected().unwrap_or(0);
+                if let Some((word, _)) = self.list.get(index).cloned() {
+                    self.set_search_term(word);
+                }
+            }
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.search_term.chars().count(),
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.search_term.chars().count()),
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                let at = self.byte_offset(self.cursor);
+                self.search_term.remove(at);
+                self.update_list();
+            }
+            KeyCode::Delete if self.cursor < self.search_term.chars().count() => {
+                let at = self.byte_offset(self.cursor);
+                self.search_term.remove(at);
+                self.update_list();
+            }
+            KeyCode::Char(c) if !ctrl => {
+                let at = self.byte_offset(self.cursor);
+                self.search_term.insert(at, c);
+                self.cursor += 1;
+                self.update_list();
+            }
+            _ => {}
+        }
+    }
+
+    fn byte_offset(&self, chars: usize) -> usize {
+        self.search_term
+            .char_indices()
+            .nth(chars)
+            .map(|(offset, _)| offset)
+            .unwrap_or(self.search_term.len())
+    }
+
+    fn set_search_term(&mut self, term: String) {
+        self.cursor = term.chars().count();
+        self.search_term = term;
+        self.update_list();
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if self.list.is_empty() {
+            return;
+        }
+        let last = self.list.len() as isize - 1;
+        let next = match self.list_state.selected() {
+            Some(current) => (current as isize + delta).clamp(0, last),
+            None if delta > 0 => 0,
+            None => last,
+        };
+        self.list_state.select(Some(next as usize));
+    }
+
+    // Refresh the side list from the search box: prefix suggestions, Chinese-to-English results,
+    // or the recent history when the box is empty
+    fn update_list(&mut self) {
+        self.list_state.select(None);
+
+        if self.search_term.trim().is_empty() {
+            self.list_mode = ListMode::History;
+            self.list = self.search_history.iter().rev().map(|word| (word.clone(), String::new())).collect();
+            return;
+        }
+
+        if cjk::contains_cjk(&self.search_term) {
+            self.list_mode = ListMode::Reverse;
+            self.list = self.dictionary.reverse_search(&self.search_term, 50).unwrap_or_default();
+            return;
+        }
+
+        self.list_mode = ListMode::Suggestions;
+        self.list = self.dictionary.search_words(&self.search_term, 20).unwrap_or_default();
+    }
+
+    fn search_word(&mut self) {
+        if let Some((word, _)) = self.list_state.selected().and_then(|i| self.list.get(i)).cloned() {
+            self.open_entry(word);
+            return;
+        }
+
+        if self.search_term.trim().is_empty() {
+            return;
+        }
+
+        if cjk::contains_cjk(&self.search_term) {
+            // Open the best match of the Chinese-to-English lookup
+            match self.list.first().cloned() {
+                Some((word, _)) => self.open_entry(word),
+                None => self.message = Some(format!("No English words found for '{}'", self.search_term)),
+            }
+            return;
+        }
+
+        let term = self.search_term.clone();
+        self.lookup(&term);
+    }
+
+    // Open the entry for `word` directly
+    fn open_entry(&mut self, word: String) {
+        self.cursor = word.chars().count();
+        self.search_term = word.clone();
+        self.lookup(&word);
+    }
+
+    fn lookup(&mut self, term: &str) {
+        self.scroll = 0;
+        match self.dictionary.lookup_all(term) {
+            Ok(results) if !results.is_empty() => {
+                self.add_to_history(&results[0].definition.word);
+                self.results = results;
+                self.message = None;
+                self.update_list();
+            }
+            Ok(_) => {
+                self.results.clear();
+                self.message = Some(format!("Word '{}' not found", term));
+                self.list_mode = ListMode::DidYouMean;
+                self.list = self
+                    .dictionary
+                    .suggest_similar(term, 5)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|word| (word, String::new()))
+                    .collect();
+                self.list_state.select(None);
+            }
+            Err(e) => {
+                self.results.clear();
+                self.message = Some(format!("Error looking up word: {}", e));
+            }
+        }
+    }
+
+    fn add_to_history(&mut self, word: &str) {
+        self.search_history.retain(|w| w != word);
+        self.search_history.push(word.to_string());
+        if self.search_history.len() > HISTORY_LIMIT {
+            self.search_history.remove(0);
+        }
+    }
+
+    fn clear_search(&mut self) {
+        self.search_term.clear();
+        self.cursor = 0;
+        self.message = None;
+        self.update_list();
+    }
+
+    // Reload the displayed entry when the watcher reindexed it
+    fn apply_watcher_changes(&mut self) {
+        let changed: Vec<String> = std::mem::take(&mut *self.watcher_changes.lock().unwrap());
+        if changed.is_empty() {
+            return;
+        }
+        self.message = Some(format!("Reindexed: {}", changed.join(", ")));
+        if let Some(word) = self.results.first().map(|r| r.definition.word.clone())
+            && changed.contains(&word)
+        {
+            let scroll = self.scroll;
+            self.lookup(&word);
+            self.scroll = scroll;
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [search_area, body_area, status_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [list_area, entry_area] =
+            Layout::horizontal([Constraint::Length(32), Constraint::Min(1)]).areas(body_area);
+
+        // Search box
+        let search = Paragraph::new(self.search_term.as_str()).block(Block::bordered().title(" Search "));
+        frame.render_widget(search, search_area);
+        let cursor_x: usize = self.search_term.chars().take(self.cursor).map(char_width).sum();
+        frame.set_cursor_position(Position::new(search_area.x + 1 + cursor_x as u16, search_area.y + 1));
+
+        // Suggestions / results / history
+        let items: Vec<ListItem> = self
+            .list
+            .iter()
+            .map(|(word, concise)| {
+                let mut spans = vec![Span::raw(word.clone())];
+                if !concise.is_empty() {
+                    spans.push(Span::raw("  "));
+                    spans.push(Span::styled(concise.clone(), Style::new().fg(CHINESE)));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(self.list_mode.title()))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, list_area, &mut self.list_state);
+
+        // Entry
+        let show_source = self.dictionary.has_multiple_sources();
+        let mut lines = Vec::new();
+        for (i, result) in self.results.iter().enumerate() {
+            if i > 0 {
+                lines.push(Line::default());
+                lines.push(Line::from(format!("Also in {}", result.source)).bold().underlined());
+            } else if show_source {
+                lines.push(Line::from(format!("[{}]", result.source)).dim());
+            }
+            lines.extend(definition_lines(&result.definition, result.inflection.as_ref()));
+        }
+        let entry = Paragraph::new(lines)
+            .block(Block::bordered())
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(entry, entry_area);
+
+        // Status line: the last message, otherwise the key bindings
+        let status = match &self.message {
+            Some(message) => Line::from(message.as_str()).yellow(),
+            None => Line::from("Enter look up · ↑↓ select · Tab complete · PgUp/PgDn scroll · Esc clear/quit").dim(),
+        };
+        frame.render_widget(status, status_area);
+    }
+}
+
+// Styled lines for an entry, in the same order as the GUI
+fn definition_lines(definition: &WordDefinition, inflection: Option<&Inflection>) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+
+    if let Some(inflection) = inflection {
+        lines.push(Line::from(inflection.describe()).italic().dim());
+    }
+
+    lines.push(Line::from(definition.word.clone()).bold());
+    if let Some(phonetic) = definition.phonetic.as_deref().filter(|p| !p.is_empty()) {
+        lines.push(Line::from(format!("/{}/", phonetic)));
+    }
+    if let Some(concise) = &definition.concise_definition {
+        lines.push(Line::from(concise.clone()).fg(CHINESE));
+    }
+    lines.push(Line::default());
+
+    for (i, meaning) in definition.meanings.iter().flatten().enumerate() {
+        lines.push(Line::from(format!("{}. {}", i + 1, meaning.part_of_speech)).fg(PART_OF_SPEECH).bold());
+        lines.push(Line::from(meaning.explanation_en.clone()));
+        if let Some(explanation_cn) = &meaning.explanation_cn {
+            lines.push(Line::from(explanation_cn.clone()).fg(CHINESE));
+        }
+        if let Some(example) = &meaning.example_en {
+            lines.push(Line::from(format!("  {}", example)).italic());
+        }
+        if let Some(example_cn) = &meaning.example_cn {
+            lines.push(Line::from(format!("  {}", example_cn)).fg(CHINESE).italic());
+        }
+        lines.push(Line::default());
+    }
+
+    for comparison in definition.comparisons.iter().flatten() {
+        lines.push(Line::from(format!("  {}", comparison.word)).bold());
+        if let Some(analysis) = &comparison.analysis {
+            lines.push(Line::from(analysis.clone()).fg(CHINESE));
+        }
+        lines.push(Line::default());
+    }
+
+    lines
+}
+
+// Display width of a character in terminal cells (CJK characters take two)
+fn char_width(c: char) -> usize {
+    if cjk::is_cjk(c) || ('\u{3000}'..='\u{303F}').contains(&c) || ('\u{FF00}'..='\u{FF60}').contains(&c) {
+        2
+    } else {
+        1
+    }
+}
@@ -0,0 +1,498 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fs;
+use std::io::BufRead;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::import;
+use crate::models::{normalize_headword, WordDefinition};
+use crate::pack;
+use crate::scan;
+
+// 词条校验：检查每个 JSON 文件（或词典包的每一行）是否符合 WordDefinition 的结构，
+// 以及内容规则（空释义、未知词性、文件名与词头不符、重复词头、比较的词不存在）。
+// 建立索引时这些问题只会被跳过或忽略，校验给出带文件、JSON 路径和严重程度的报告。
+
+// words 中使用的词性名称（不区分大小写）
+const KNOWN_PARTS_OF_SPEECH: [&str; 24] = [
+    "noun",
+    "verb",
+    "adjective",
+    "adverb",
+    "pronoun",
+    "preposition",
+    "conjunction",
+    "interjection",
+    "exclamation",
+    "determiner",
+    "article",
+    "numeral",
+    "abbreviation",
+    "auxiliary verb",
+    "modal verb",
+    "phrasal verb",
+    "phrase",
+    "idiom",
+    "prefix",
+    "suffix",
+    "plural",
+    "proper noun",
+    "combining form",
+    "particle",
+];
+
+const ENTRY_FIELDS: [&str; 6] = ["word", "pronunciation", "forms", "concise_definition", "definitions", "comparison"];
+const MEANING_FIELDS: [&str; 5] = ["pos", "explanation_en", "explanation_cn", "example_en", "example_cn"];
+const COMPARISON_FIELDS: [&str; 2] = ["word_to_compare", "analysis"];
+
+// 错误：词条无法建立索引或查词结果不确定；警告：内容有问题但仍会被索引
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Issue {
+    // 相对 words 目录的路径；词典包为包文件的路径
+    pub file: String,
+    // 词典包中的行号，或 JSON 语法错误的位置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    // JSONPath，例如 $.definitions[1].explanation_en
+    pub path: String,
+    pub severity: Severity,
+    // 问题类型，例如 empty-explanation、unknown-pos
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl Issue {
+    // a/abandon.json: warning: $.definitions[1].pos: unknown part of speech "verbb" (unknown-pos)
+    pub fn describe(&self) -> String {
+        let location = match (self.line, self.column) {
+            (Some(line), Some(column)) => format!("{}:{}:{}", self.file, line, column),
+            (Some(line), None) => format!("{}:{}", self.file, line),
+            _ => self.file.clone(),
+        };
+        format!(
+            "{}: {}: {}: {} ({})",
+            location,
+            self.severity.label(),
+            self.path,
+            self.message,
+            self.code
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ValidationReport {
+    // 检查的词条数（文件数或词典包的行数）
+    pub entries: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub issues: Vec<Issue>,
+}
+
+// 跨文件检查需要的信息
+struct EntryInfo {
+    file: String,
+    line: Option<usize>,
+    headword: String,
+    forms: Vec<String>,
+    // (JSON 路径, 比较的词)
+    comparisons: Vec<(String, String)>,
+}
+
+// 校验 words 目录（包括子目录）或词典包中的全部词条
+pub fn validate(source: &Path) -> Result<ValidationReport, Box<dyn Error>> {
+    let mut issues = Vec::new();
+    let mut entries = Vec::new();
+    let mut count = 0;
+
+    if pack::is_pack(source) {
+        let file = source.display().to_string();
+        let reader = pack::open_pack(source).map_err(|e| format!("Failed to open {}: {}", file, e))?;
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            count += 1;
+            let mut checker = Checker::new(file.clone(), Some(number + 1), &mut issues);
+            entries.extend(checker.check_json(&line, None));
+        }
+    } else {
+        for word_file in scan::word_files(source)? {
+            count += 1;
+            let mut checker = Checker::new(word_file.rel_path.clone(), None, &mut issues);
+            match fs::read_to_string(&word_file.path) {
+                Ok(data) => {
+                    let file_name = word_file.path.file_name().map(|name| name.to_string_lossy().into_owned());
+                    entries.extend(checker.check_json(&data, file_name.as_deref()));
+                }
+                Err(e) => checker.push("$", Severity::Error, "unreadable", format!("failed to read file: {}", e)),
+            }
+        }
+    }
+
+    check_across_entries(&entries, &mut issues);
+    issues.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+
+    let errors = issues.iter().filter(|issue| issue.severity == Severity::Error).count();
+    Ok(ValidationReport {
+        entries: count,
+        errors,
+        warnings: issues.len() - errors,
+        issues,
+    })
+}
+
+// 文件名与词头比较的形式：去掉扩展名和导入时加的序号（"AC_DC (2).json"），下划线视为空格（give_up.json 即 "give up"）
+fn file_name_key(file_name: &str) -> String {
+    let stem = Path::new(file_name).file_stem().map(|stem| stem.to_string_lossy()).unwrap_or_default();
+    let stem = stem
+        .strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once(" ("))
+        .filter(|(_, n)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        .map_or(&*stem, |(stem, _)| stem);
+    normalize_headword(&import::file_name_for(stem).replace('_', " "))
+}
+
+// 重复词头、比较的词不存在
+fn check_across_entries(entries: &[EntryInfo], issues: &mut Vec<Issue>) {
+    let mut first_seen: HashMap<&str, &EntryInfo> = HashMap::new();
+    let mut known: HashSet<&str> = HashSet::new();
+    for entry in entries {
+        known.insert(&entry.headword);
+        known.extend(entry.forms.iter().map(String::as_str));
+    }
+
+    for entry in entries {
+        let mut checker = Checker::new(entry.file.clone(), entry.line, issues);
+        match first_seen.get(entry.headword.as_str()) {
+            Some(first) => checker.push(
+                "$.word",
+                Severity::Error,
+                "duplicate-headword",
+                format!("headword \"{}\" is already defined in {}", entry.headword, first.location()),
+            ),
+            None => {
+                first_seen.insert(&entry.headword, entry);
+            }
+        }
+
+        for (path, word) in &entry.comparisons {
+            if !known.contains(normalize_headword(word).as_str()) {
+                checker.push(
+                    path,
+                    Severity::Warning,
+                    "missing-comparison",
+                    format!("compared word \"{}\" has no entry", word),
+                );
+            }
+        }
+    }
+}
+
+impl EntryInfo {
+    fn location(&self) -> String {
+        match self.line {
+            Some(line) => format!("{}:{}", self.file, line),
+            None => self.file.clone(),
+        }
+    }
+}
+
+struct Checker<'a> {
+    file: String,
+    line: Option<usize>,
+    issues: &'a mut Vec<Issue>,
+}
+
+impl<'a> Checker<'a> {
+    fn new(file: String, line: Option<usize>, issues: &'a mut Vec<Issue>) -> Self {
+        Checker { file, line, issues }
+    }
+
+    fn push(&mut self, path: &str, severity: Severity, code: &'static str, message: String) {
+        self.issues.push(Issue {
+            file: self.file.clone(),
+            line: self.line,
+            column: None,
+            path: path.to_string(),
+            severity,
+            code,
+            message,
+        });
+    }
+
+    // 检查一个词条；file_name 为 words 目录中的文件名（词典包中的词条没有）。
+    // 结构正确时返回跨文件检查需要的信息
+    fn check_json(&mut self, data: &str, file_name: Option<&str>) -> Option<EntryInfo> {
+        let value: Value = match serde_json::from_str(data) {
+            Ok(value) => value,
+            Err(e) => {
+                // 词典包中行号就是包的行，错误位置只给出列
+                let (line, column) = match self.line {
+                    Some(line) => (line, e.column()),
+                    None => (e.line(), e.column()),
+                };
+                self.issues.push(Issue {
+                    file: self.file.clone(),
+                    line: Some(line),
+                    column: Some(column),
+                    path: "$".to_string(),
+                    severity: Severity::Error,
+                    code: "invalid-json",
+                    message: e.to_string(),
+                });
+                return None;
+            }
+        };
+
+        let Some(object) = value.as_object() else {
+            self.push("$", Severity::Error, "schema", "entry must be a JSON object".to_string());
+            return None;
+        };
+        let errors_before = self.error_count();
+        self.check_entry(object);
+        if self.error_count() > errors_before {
+            return None;
+        }
+
+        // 结构检查没有发现的问题（理论上不会发生）也要报告，保证通过校验的词条都能被索引
+        let definition = match WordDefinition::deserialize(&value) {
+            Ok(definition) => definition,
+            Err(e) => {
+                self.push("$", Severity::Error, "schema", e.to_string());
+                return None;
+            }
+        };
+
+        let headword = normalize_headword(&definition.word);
+        if headword.is_empty() {
+            self.push("$.word", Severity::Error, "empty-word", "word is empty".to_string());
+            return None;
+        }
+        if let Some(file_name) = file_name {
+            let expected = import::file_name_for(&definition.word);
+            if file_name_key(file_name) != file_name_key(&expected) {
+                self.push(
+                    "$.word",
+                    Severity::Warning,
+                    "file-name-mismatch",
+                    format!("file name does not match word \"{}\" (expected {})", definition.word, expected),
+                );
+            }
+        }
+
+        let comparisons = definition
+            .comparisons
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(i, comparison)| (format!("$.comparison[{}].word_to_compare", i), comparison.word.clone()))
+            .collect();
+        Some(EntryInfo {
+            file: self.file.clone(),
+            line: self.line,
+            headword,
+            forms: definition.inflected_forms().into_keys().collect(),
+            comparisons,
+        })
+    }
+
+    fn error_count(&self) -> usize {
+        self.issues.iter().filter(|issue| issue.severity == Severity::Error).count()
+    }
+
+    fn check_entry(&mut self, object: &Map<String, Value>) {
+        self.check_unknown_fields("$", object, &ENTRY_FIELDS);
+        self.check_string("$", object, "word", true);
+        self.check_string("$", object, "pronunciation", false);
+        self.check_string("$", object, "concise_definition", false);
+
+        match object.get("forms") {
+            None | Some(Value::Null) => {}
+            Some(Value::Object(forms)) => {
+                for (kind, value) in forms {
+                    let path = format!("$.forms.{}", kind);
+                    let valid = match value {
+                        Value::String(_) => true,
+                        Value::Array(items) => items.iter().all(Value::is_string),
+                        _ => false,
+                    };
+                    if !valid {
+                        self.push(
+                            &path,
+                            Severity::Warning,
+                            "invalid-form",
+                            "form must be a string or a list of strings; it is ignored".to_string(),
+                        );
+                    }
+                }
+            }
+            Some(_) => self.push("$.forms", Severity::Error, "schema", "forms must be an object".to_string()),
+        }
+
+        if let Some(meanings) = self.check_array(object, "definitions") {
+            if meanings.is_empty() {
+                self.push("$.definitions", Severity::Warning, "no-definitions", "entry has no definitions".to_string());
+            }
+            for (i, meaning) in meanings.iter().enumerate() {
+                let path = format!("$.definitions[{}]", i);
+                match meaning.as_object() {
+                    Some(meaning) => self.check_meaning(&path, meaning),
+                    None => self.push(&path, Severity::Error, "schema", "definition must be an object".to_string()),
+                }
+            }
+        }
+
+        if let Some(comparisons) = self.check_array(object, "comparison") {
+            for (i, comparison) in comparisons.iter().enumerate() {
+                let path = format!("$.comparison[{}]", i);
+                match comparison.as_object() {
+                    Some(comparison) => {
+                        self.check_unknown_fields(&path, comparison, &COMPARISON_FIELDS);
+                        self.check_string(&path, comparison, "word_to_compare", true);
+                        self.check_string(&path, comparison, "analysis", false);
+                    }
+                    None => self.push(&path, Severity::Error, "schema", "comparison must be an object".to_string()),
+                }
+            }
+        }
+    }
+
+    fn check_meaning(&mut self, path: &str, meaning: &Map<String, Value>) {
+        self.check_unknown_fields(path, meaning, &MEANING_FIELDS);
+        let pos = self.check_string(path, meaning, "pos", true);
+        let explanation_en = self.check_string(path, meaning, "explanation_en", true);
+        let explanation_cn = self.check_string(path, meaning, "explanation_cn", false);
+        self.check_string(path, meaning, "example_en", false);
+        self.check_string(path, meaning, "example_cn", false);
+
+        match pos.map(str::trim) {
+            Some("") => self.push(
+                &format!("{}.pos", path),
+                Severity::Warning,
+                "missing-pos",
+                "part of speech is empty".to_string(),
+            ),
+            Some(pos) if !KNOWN_PARTS_OF_SPEECH.iter().any(|known| known.eq_ignore_ascii_case(pos)) => self.push(
+                &format!("{}.pos", path),
+                Severity::Warning,
+                "unknown-pos",
+                format!("unknown part of speech \"{}\"", pos),
+            ),
+            _ => {}
+        }
+
+        // 只有中文释义时为警告（导入的词典常见），中英文都没有时为错误
+        if let Some(explanation_en) = explanation_en
+            && explanation_en.trim().is_empty()
+        {
+            let has_cn = explanation_cn.is_some_and(|cn| !cn.trim().is_empty());
+            let (severity, message) = if has_cn {
+                (Severity::Warning, "explanation_en is empty")
+            } else {
+                (Severity::Error, "definition has neither explanation_en nor explanation_cn")
+            };
+            self.push(&format!("{}.explanation_en", path), severity, "empty-explanation", message.to_string());
+        }
+    }
+
+    // 字段为字符串时返回其内容；缺少必需字段或类型不对时报告错误。可选字段允许为 null
+    fn check_string<'v>(
+        &mut self,
+        path: &str,
+        object: &'v Map<String, Value>,
+        field: &str,
+        required: bool,
+    ) -> Option<&'v str> {
+        let field_path = format!("{}.{}", path, field);
+        match object.get(field) {
+            Some(Value::String(text)) => Some(text),
+            None | Some(Value::Null) if !required => None,
+            None => {
+                self.push(&field_path, Severity::Error, "schema", format!("missing required field {}", field));
+                None
+            }
+            Some(_) => {
+                let expected = if required { "a string" } else { "a string or null" };
+                self.push(&field_path, Severity::Error, "schema", format!("{} must be {}", field, expected));
+                None
+            }
+        }
+    }
+
+    // 可选的数组字段：缺少或为 null 时返回 None，不是数组时报告错误
+    fn check_array<'v>(&mut self, object: &'v Map<String, Value>, field: &str) -> Option<&'v Vec<Value>> {
+        match object.get(field) {
+            None | Some(Value::Null) => None,
+            Some(Value::Array(items)) => Some(items),
+            Some(_) => {
+                self.push(&format!("$.{}", field), Severity::Error, "schema", format!("{} must be an array", field));
+                None
+            }
+        }
+    }
+
+    // 模型中没有的字段不会被索引，多半是拼写错误
+    fn check_unknown_fields(&mut self, path: &str, object: &Map<String, Value>, known: &[&str]) {
+        for field in object.keys().filter(|field| !known.contains(&field.as_str())) {
+            self.push(
+                &format!("{}.{}", path, field),
+                Severity::Warning,
+                "unknown-field",
+                format!("unknown field {}; it is ignored", field),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_name_issues(file_name: &str, word: &str) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        let data = serde_json::json!({ "word": word }).to_string();
+        Checker::new(file_name.to_string(), None, &mut issues).check_json(&data, Some(file_name));
+        issues.retain(|issue| issue.code == "file-name-mismatch");
+        issues
+    }
+
+    #[test]
+    fn accepts_file_names_derived_from_the_word() {
+        assert!(file_name_issues("give.json", "give").is_empty());
+        assert!(file_name_issues("Give.json", "give").is_empty());
+        assert!(file_name_issues("give up.json", "give up").is_empty());
+        assert!(file_name_issues("give_up.json", "give up").is_empty());
+        assert!(file_name_issues("AC_DC.json", "AC/DC").is_empty());
+        assert!(file_name_issues("_net.json", ".net").is_empty());
+        assert!(file_name_issues("polish (2).json", "polish").is_empty());
+    }
+
+    #[test]
+    fn reports_file_names_for_other_words() {
+        assert_eq!(file_name_issues("take.json", "give").len(), 1);
+        assert_eq!(file_name_issues("give-up.json", "give up").len(), 1);
+    }
+}
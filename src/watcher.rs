@@ -0,0 +1,143 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use notify_debouncer_mini::notify::{self, Event, EventHandler, RecommendedWatcher, RecursiveMode, Watcher, WatcherKind};
+use notify_debouncer_mini::{new_debouncer_opt, Config, DebounceEventResult, Debouncer};
+
+use crate::dictionary::{Dictionary, SyncSummary};
+use crate::pack;
+use crate::scan;
+
+// 编辑器保存文件时通常会连续产生多个事件，合并 500ms 内的变化
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(500);
+
+// 遍历目录和读取文件会产生打开、关闭（Access）事件，它们不是修改；在交给 debouncer 之前丢掉，
+// 否则同步时遍历子目录、读取忽略文件产生的事件又会触发下一次同步
+struct ChangeWatcher(RecommendedWatcher);
+
+impl Watcher for ChangeWatcher {
+    fn new<F: EventHandler>(mut event_handler: F, config: notify::Config) -> notify::Result<Self> {
+        let watcher = RecommendedWatcher::new(
+            move |event: notify::Result<Event>| {
+                if !matches!(&event, Ok(event) if event.kind.is_access()) {
+                    event_handler.handle_event(event);
+                }
+            },
+            config,
+        )?;
+        Ok(ChangeWatcher(watcher))
+    }
+
+    fn watch(&mut self, path: &Path, recursive_mode: RecursiveMode) -> notify::Result<()> {
+        self.0.watch(path, recursive_mode)
+    }
+
+    fn unwatch(&mut self, path: &Path) -> notify::Result<()> {
+        self.0.unwatch(path)
+    }
+
+    fn kind() -> WatcherKind {
+        RecommendedWatcher::kind()
+    }
+}
+
+// 监视 words 目录（包括子目录），JSON 文件变化时只重新索引被修改的词条
+pub struct WordsWatcher {
+    _debouncer: Debouncer<ChangeWatcher>,
+}
+
+impl WordsWatcher {
+    // on_reindex 在监视线程中调用，参数为本次重新索引的结果
+    pub fn start<F>(dictionary: Arc<Dictionary>, on_reindex: F) -> Result<Self, Box<dyn std::error::Error>>
+    where
+        F: Fn(Result<SyncSummary, String>) + Send + 'static,
+    {
+        let words_dir = Path::new(dictionary.words_directory()).canonicalize()?;
+        // 词典包：监视包文件所在的目录，只关心包文件本身
+        let pack_file = pack::is_pack(&words_dir).then(|| words_dir.clone());
+        let watch_dir = match &pack_file {
+            Some(pack_file) => pack_file.parent().map(Path::to_path_buf).unwrap_or_default(),
+            None => words_dir.clone(),
+        };
+        let mode = if pack_file.is_some() {
+            RecursiveMode::NonRecursive
+        } else {
+            RecursiveMode::Recursive
+        };
+        let watched_dir = words_dir.clone();
+        // 索引目录可能在 words 目录中，忽略其中（以及 .building、.old 临时目录中）的变化
+        let index_prefix = absolute_path(Path::new(dictionary.index_path())).to_string_lossy().into_owned();
+
+        let config = Config::default().with_timeout(DEBOUNCE_TIMEOUT);
+        let mut debouncer = new_debouncer_opt::<_, ChangeWatcher>(config, move |result: DebounceEventResult| {
+            let events = match result {
+                Ok(events) => events,
+                Err(e) => {
+                    on_reindex(Err(format!("Words directory watcher error: {}", e)));
+                    return;
+                }
+            };
+
+            // 只关心 words 目录下未被忽略的 JSON 文件、忽略文件和子目录本身（包括已删除的索引过的目录），
+            // 忽略索引目录中的文件和编辑器的临时文件
+            let paths: Vec<PathBuf> = events
+                .into_iter()
+                .map(|event| event.path)
+                .filter(|path| match &pack_file {
+                    Some(pack_file) => path == pack_file,
+                    None => {
+                        !path.to_string_lossy().starts_with(&index_prefix)
+                            && (path.file_name().is_some_and(|name| name == scan::IGNORE_FILE)
+                                || ((scan::is_word_file(path)
+                                    || path.is_dir()
+                                    || (!path.exists() && dictionary.is_indexed_directory(path)))
+                                    && !scan::is_ignored(&watched_dir, path)))
+                    }
+                })
+                .collect();
+
+            if paths.is_empty() {
+                return;
+            }
+
+            on_reindex(dictionary.reindex_files(&paths).map_err(|e| e.to_string()));
+        })?;
+
+        debouncer.watcher().watch(&watch_dir, mode)?;
+
+        Ok(WordsWatcher { _debouncer: debouncer })
+    }
+}
+
+// 监视器给出的是绝对路径；索引目录还不存在时无法 canonicalize，按当前目录补全相对路径，
+// 否则相对路径的前缀永远匹配不上
+fn absolute_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| match std::env::current_dir() {
+        Ok(current_dir) => current_dir.join(path),
+        Err(_) => path.to_path_buf(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_paths_are_made_absolute() {
+        let temp = tempfile::tempdir().unwrap();
+        let existing = temp.path().join("index");
+        std::fs::create_dir(&existing).unwrap();
+        assert_eq!(absolute_path(&existing), existing.canonicalize().unwrap());
+
+        // 不存在的相对路径按当前目录补全
+        let missing = Path::new("no-such-dir").join(".index");
+        let absolute = absolute_path(&missing);
+        assert!(absolute.is_absolute());
+        assert_eq!(absolute, std::env::current_dir().unwrap().join(&missing));
+
+        // 已经是绝对路径的不变
+        let missing = temp.path().join("missing");
+        assert_eq!(absolute_path(&missing), missing);
+    }
+}